@@ -0,0 +1,55 @@
+//! Runs an `EvmClientRegistry` against a local mock chain, without network access or API keys.
+
+use std::collections::HashMap;
+
+use alloy::primitives::address;
+use anyhow::Result;
+use mock_chain::{Fixture, MockChain};
+use multichain_client::EvmClientRegistry;
+use tracing::{info, level_filters::LevelFilter};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env()?,
+        )
+        .init();
+
+    let fixture = Fixture::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dev.json"))?;
+    let mock = MockChain::start(fixture).await?;
+    info!("Mock chain running at {}", mock.url());
+
+    let client = mock
+        .chain_metadata("Mock Devnet", "dev")
+        .create_rpc_client("")?;
+    client.add_tokens_from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/dev-tokens.json"
+    ))?;
+    let registry = EvmClientRegistry::new(HashMap::from([("dev".to_string(), client)]));
+
+    let address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    for (chain, balance) in registry.get_native_balances(address).await? {
+        info!("Native balance for {address} on {chain}: {balance}");
+    }
+    for (chain, balances) in registry.get_token_balances(address).await {
+        for balance in balances {
+            info!(
+                "Token {} on {chain} = {}",
+                balance.token.symbol, balance.balance
+            );
+        }
+    }
+    for (chain, transactions) in registry.get_transactions(address, 1, 10).await? {
+        info!(
+            "{} transactions for {address} on {chain}",
+            transactions.len()
+        );
+    }
+
+    Ok(())
+}
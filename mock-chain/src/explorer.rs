@@ -0,0 +1,94 @@
+//! The Etherscan-style explorer API served by [`MockChain`](crate::MockChain).
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy::primitives::Address;
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::fixture::Fixture;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExplorerQuery {
+    module: Option<String>,
+    action: Option<String>,
+    address: Option<Address>,
+    page: Option<usize>,
+    offset: Option<usize>,
+    sort: Option<String>,
+}
+
+/// `GET /api`: answers `module=account&action=txlist`, `module=account&action=txlistinternal`
+/// and `module=contract&action=getabi` from the fixture.
+pub(crate) async fn handle(
+    State(fixture): State<Arc<Fixture>>,
+    Query(query): Query<ExplorerQuery>,
+) -> Json<Value> {
+    Json(
+        match (
+            query.module.as_deref(),
+            query.action.as_deref(),
+            query.address,
+        ) {
+            (Some("account"), Some("txlist"), Some(address)) => {
+                txlist(&fixture.transactions, &query, address)
+            }
+            (Some("account"), Some("txlistinternal"), Some(address)) => {
+                txlist(&fixture.internal_transactions, &query, address)
+            }
+            (Some("contract"), Some("getabi"), Some(address)) => getabi(&fixture, address),
+            _ => json!({
+                "status": "0",
+                "message": "NOTOK",
+                "result": "Error! Unsupported module, action or missing address"
+            }),
+        },
+    )
+}
+
+/// Pages through the `transactions` listed for `address`, newest first.
+fn txlist(
+    transactions: &HashMap<Address, Vec<Value>>,
+    query: &ExplorerQuery,
+    address: Address,
+) -> Value {
+    let mut transactions = transactions.get(&address).cloned().unwrap_or_default();
+    if query.sort.as_deref() == Some("asc") {
+        transactions.reverse();
+    }
+
+    // an offset of 0 returns every transaction, like the explorer without paging params
+    let offset = query.offset.unwrap_or(0);
+    let page: Vec<Value> = if offset == 0 {
+        transactions
+    } else {
+        let page = query.page.unwrap_or(1).max(1);
+        transactions
+            .into_iter()
+            .skip((page - 1) * offset)
+            .take(offset)
+            .collect()
+    };
+
+    if page.is_empty() {
+        json!({ "status": "0", "message": "No transactions found", "result": [] })
+    } else {
+        json!({ "status": "1", "message": "OK", "result": page })
+    }
+}
+
+fn getabi(fixture: &Fixture, address: Address) -> Value {
+    match fixture.abis.get(&address) {
+        // the explorer returns the ABI as a JSON-encoded string
+        Some(abi) => json!({ "status": "1", "message": "OK", "result": abi.to_string() }),
+        None => json!({
+            "status": "0",
+            "message": "NOTOK",
+            "result": "Contract source code not verified"
+        }),
+    }
+}
@@ -0,0 +1,102 @@
+//! Fixture data a [`MockChain`](crate::MockChain) is seeded from.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use alloy::{
+    primitives::{Address, B256, Bytes, U256},
+    rpc::types::Log,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The state served by a [`MockChain`](crate::MockChain).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Fixture {
+    /// Chain ID returned by `eth_chainId`
+    #[serde(rename = "chainId")]
+    pub chain_id: u64,
+
+    /// Block number returned by `eth_blockNumber`
+    #[serde(rename = "blockNumber", default)]
+    pub block_number: u64,
+
+    /// Native balances returned by `eth_getBalance`; unknown addresses have a zero balance
+    #[serde(default)]
+    pub balances: HashMap<Address, U256>,
+
+    /// ERC-20 balances, keyed by token then owner, returned by `balanceOf` calls
+    #[serde(default)]
+    pub tokens: HashMap<Address, HashMap<Address, U256>>,
+
+    /// Account code returned by `eth_getCode`; unknown addresses have no code
+    #[serde(default)]
+    pub code: HashMap<Address, Bytes>,
+
+    /// Raw `eth_call` results for any other call
+    #[serde(default)]
+    pub calls: Vec<CallFixture>,
+
+    /// Storage slots, keyed by contract then slot, returned by `eth_getStorageAt`; unknown slots
+    /// are zero
+    #[serde(default)]
+    pub storage: HashMap<Address, HashMap<B256, B256>>,
+
+    /// Logs returned by `eth_getLogs`
+    #[serde(default)]
+    pub logs: Vec<Log>,
+
+    /// Maximum number of logs a single `eth_getLogs` call may return before failing with
+    /// "query returned more than N results", like Infura and Alchemy
+    #[serde(rename = "maxLogResults", default)]
+    pub max_log_results: Option<usize>,
+
+    /// Block headers returned by `eth_getBlockByNumber`, in the node's own format
+    #[serde(default)]
+    pub blocks: Vec<Value>,
+
+    /// Transactions returned by `eth_getTransactionByHash`, in the node's own format
+    #[serde(rename = "rpcTransactions", default)]
+    pub rpc_transactions: Vec<Value>,
+
+    /// Receipts returned by `eth_getTransactionReceipt`, in the node's own format; transactions
+    /// without one are pending
+    #[serde(default)]
+    pub receipts: Vec<Value>,
+
+    /// Explorer transactions, keyed by address, newest first
+    ///
+    /// Entries use the explorer's own format (stringified numbers), so they can be copied from
+    /// real `txlist` responses.
+    #[serde(default)]
+    pub transactions: HashMap<Address, Vec<Value>>,
+
+    /// Explorer internal transactions, keyed by address, newest first, in the format of
+    /// `txlistinternal` responses
+    #[serde(rename = "internalTransactions", default)]
+    pub internal_transactions: HashMap<Address, Vec<Value>>,
+
+    /// Verified contract ABIs returned by `getabi`; other contracts are unverified
+    #[serde(default)]
+    pub abis: HashMap<Address, Value>,
+}
+
+/// A scripted `eth_call` result.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CallFixture {
+    /// Contract being called
+    pub to: Address,
+
+    /// Exact calldata of the call
+    pub data: Bytes,
+
+    /// Return data of the call
+    pub result: Bytes,
+}
+
+impl Fixture {
+    /// Reads a fixture from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+}
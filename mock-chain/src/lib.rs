@@ -0,0 +1,32 @@
+//! A local mock JSON-RPC node and block explorer for offline integration testing.
+//!
+//! [`MockChain`] starts an HTTP server seeded from a [`Fixture`]. It speaks:
+//! - the `eth_chainId`, `eth_blockNumber`, `eth_getBlockByNumber`, `eth_getBalance`, `eth_getCode`,
+//!   `eth_getStorageAt`, `eth_getLogs`, `eth_getTransactionByHash`, `eth_getTransactionReceipt`
+//!   and `eth_call` JSON-RPC methods, including batches, on `POST /`
+//! - the same methods over a WebSocket on `GET /`, along with `eth_subscribe` for `newHeads`
+//!   and `logs`, which replay the fixture's blocks and matching logs once per second in a loop.
+//!   [`MockChain::drop_ws_connections`] drops every open connection to test reconnecting
+//! - the Etherscan-style `account`/`txlist`, `account`/`txlistinternal` and `contract`/`getabi`
+//!   APIs on `GET /api`
+//!
+//! State is static: block parameters are accepted but ignored, so every block sees the fixture.
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! use mock_chain::{Fixture, MockChain};
+//!
+//! let mock = MockChain::start(Fixture::from_file("mock-chain/fixtures/dev.json")?).await?;
+//! let client = mock.chain_metadata("Mock Devnet", "dev").create_rpc_client("").unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+mod explorer;
+mod fixture;
+mod rpc;
+mod server;
+mod ws;
+
+pub use fixture::{CallFixture, Fixture};
+pub use server::MockChain;
@@ -0,0 +1,37 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use anyhow::Result;
+use clap::Parser;
+use mock_chain::{Fixture, MockChain};
+use tracing::{info, level_filters::LevelFilter};
+use tracing_subscriber::EnvFilter;
+
+/// Serves a fixture as a local JSON-RPC node and Etherscan-style explorer.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[clap(long, default_value = "mock-chain/fixtures/dev.json")]
+    fixture: std::path::PathBuf,
+    #[clap(long, default_value_t = 8545)]
+    port: u16,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env()?,
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let fixture = Fixture::from_file(&cli.fixture)?;
+    let mock =
+        MockChain::start_on(SocketAddr::from((Ipv4Addr::LOCALHOST, cli.port)), fixture).await?;
+    info!("🧪 Mock chain running at {}", mock.url());
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
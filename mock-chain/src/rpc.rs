@@ -0,0 +1,188 @@
+//! The JSON-RPC subset served by [`MockChain`](crate::MockChain).
+
+use std::sync::Arc;
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{Address, B256, Bytes, U64},
+    rpc::types::Filter,
+};
+use axum::{Json, extract::State};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::fixture::Fixture;
+
+/// Selector of `balanceOf(address)`.
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+/// An error returned in a JSON-RPC error response.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_params(err: impl std::fmt::Display) -> Self {
+        Self::new(-32602, format!("invalid params: {err}"))
+    }
+}
+
+/// `POST /`: handles a single JSON-RPC request or a batch.
+pub(crate) async fn handle(
+    State(fixture): State<Arc<Fixture>>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    Json(match body {
+        Value::Array(requests) => requests
+            .iter()
+            .map(|request| respond(&fixture, request))
+            .collect(),
+        request => respond(&fixture, &request),
+    })
+}
+
+/// Answers a single JSON-RPC request.
+pub(crate) fn respond(fixture: &Fixture, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let params = request.get("params").cloned().unwrap_or(json!([]));
+
+    match dispatch(fixture, method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": err.code, "message": err.message }
+        }),
+    }
+}
+
+fn dispatch(fixture: &Fixture, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "eth_chainId" => Ok(json!(U64::from(fixture.chain_id))),
+        "eth_blockNumber" => Ok(json!(U64::from(fixture.block_number))),
+        "eth_getBalance" => {
+            let (address, _block): (Address, Value) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            let balance = fixture.balances.get(&address).copied().unwrap_or_default();
+            Ok(json!(balance))
+        }
+        "eth_getCode" => {
+            let (address, _block): (Address, Value) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            let code = fixture.code.get(&address).cloned().unwrap_or_default();
+            Ok(json!(code))
+        }
+        "eth_getStorageAt" => {
+            let (address, slot, _block): (Address, B256, Value) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            let value = fixture
+                .storage
+                .get(&address)
+                .and_then(|slots| slots.get(&slot))
+                .copied()
+                .unwrap_or_default();
+            Ok(json!(value))
+        }
+        "eth_getLogs" => {
+            let (filter,): (Filter,) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            let logs: Vec<_> = fixture
+                .logs
+                .iter()
+                .filter(|log| filter.rpc_matches(log))
+                .collect();
+            match fixture.max_log_results {
+                Some(max) if logs.len() > max => Err(RpcError::new(
+                    -32005,
+                    format!("query returned more than {max} results"),
+                )),
+                _ => Ok(json!(logs)),
+            }
+        }
+        "eth_getBlockByNumber" => {
+            let (block, _full): (BlockNumberOrTag, bool) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            let number = match block {
+                BlockNumberOrTag::Number(number) => number,
+                BlockNumberOrTag::Earliest => 0,
+                _ => fixture.block_number,
+            };
+            Ok(find_by(&fixture.blocks, "number", U64::from(number)))
+        }
+        "eth_getTransactionByHash" => {
+            let (hash,): (B256,) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            Ok(find_by(&fixture.rpc_transactions, "hash", hash))
+        }
+        "eth_getTransactionReceipt" => {
+            let (hash,): (B256,) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            Ok(find_by(&fixture.receipts, "transactionHash", hash))
+        }
+        "eth_call" => {
+            let (call, _block): (Value, Value) =
+                serde_json::from_value(params).map_err(RpcError::invalid_params)?;
+            eth_call(fixture, &call).map(|result| json!(result))
+        }
+        _ => Err(RpcError::new(
+            -32601,
+            format!("the method {method} does not exist/is not available"),
+        )),
+    }
+}
+
+fn eth_call(fixture: &Fixture, call: &Value) -> Result<Bytes, RpcError> {
+    let to: Address = serde_json::from_value(call.get("to").cloned().unwrap_or_default())
+        .map_err(RpcError::invalid_params)?;
+    let data: Bytes = call
+        .get("input")
+        .or_else(|| call.get("data"))
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(RpcError::invalid_params)?
+        .unwrap_or_default();
+
+    if let Some(scripted) = fixture
+        .calls
+        .iter()
+        .find(|scripted| scripted.to == to && scripted.data == data)
+    {
+        return Ok(scripted.result.clone());
+    }
+
+    if let Some(holders) = fixture.tokens.get(&to) {
+        if data.len() == 36 && data[..4] == BALANCE_OF_SELECTOR {
+            let owner = Address::from_slice(&data[16..36]);
+            let balance = holders.get(&owner).copied().unwrap_or_default();
+            return Ok(Bytes::from(balance.to_be_bytes::<32>()));
+        }
+        return Err(RpcError::new(3, "execution reverted"));
+    }
+
+    // calls to accounts without code succeed with empty return data
+    Ok(Bytes::new())
+}
+
+/// Returns the first of `entries` whose `field` equals `value`, or `null` like a node does for
+/// unknown blocks and transactions.
+fn find_by<T: Serialize>(entries: &[Value], field: &str, value: T) -> Value {
+    let value = json!(value);
+    entries
+        .iter()
+        .find(|entry| entry.get(field) == Some(&value))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
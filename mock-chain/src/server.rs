@@ -0,0 +1,126 @@
+//! The HTTP server behind [`MockChain`].
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    Extension, Router,
+    routing::{get, post},
+};
+use multichain_client::{ChainMetaData, ExplorerConfig, NativeCurrency};
+use tokio::{net::TcpListener, task::JoinHandle};
+use tracing::error;
+
+use crate::{explorer, fixture::Fixture, rpc, ws};
+
+/// A running mock node and explorer.
+///
+/// The server is stopped when the `MockChain` is dropped.
+#[derive(Debug)]
+pub struct MockChain {
+    addr: SocketAddr,
+    chain_id: u64,
+    connections: Arc<ws::Connections>,
+    handle: JoinHandle<()>,
+}
+
+impl MockChain {
+    /// Starts a mock chain serving `fixture` on a random local port.
+    pub async fn start(fixture: Fixture) -> io::Result<Self> {
+        Self::start_on(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), fixture).await
+    }
+
+    /// Starts a mock chain serving `fixture` on `addr`.
+    pub async fn start_on(addr: SocketAddr, fixture: Fixture) -> io::Result<Self> {
+        let chain_id = fixture.chain_id;
+        let connections = Arc::new(ws::Connections::default());
+        let router = Router::new()
+            .route("/", post(rpc::handle).get(ws::handle))
+            .route("/api", get(explorer::handle))
+            .layer(Extension(connections.clone()))
+            .with_state(Arc::new(fixture));
+
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let handle = tokio::spawn(async move {
+            if let Err(err) = axum::serve(listener, router).await {
+                error!("Mock chain server failed: {err}");
+            }
+        });
+
+        Ok(Self {
+            addr,
+            chain_id,
+            connections,
+            handle,
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base URL of the server, used both as RPC URL and as explorer URL.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Returns the WebSocket RPC URL of the server.
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/", self.addr)
+    }
+
+    /// Returns the number of WebSocket connections accepted so far.
+    pub fn ws_connections(&self) -> usize {
+        self.connections.accepted()
+    }
+
+    /// Closes every open WebSocket connection without a close frame, as if the node had gone
+    /// away, so clients can be tested reconnecting.
+    pub fn drop_ws_connections(&self) {
+        self.connections.drop_all();
+    }
+
+    /// Returns chain metadata pointing both RPC, over HTTP and WebSocket, and transaction history
+    /// at the server.
+    ///
+    /// Transaction history is read through the Blockscout source, which speaks the same
+    /// Etherscan-style API without requiring an API key.
+    pub fn chain_metadata(&self, name: &str, short_name: &str) -> ChainMetaData {
+        ChainMetaData {
+            name: name.to_string(),
+            chain_id: self.chain_id,
+            short_name: short_name.to_string(),
+            network_id: self.chain_id,
+            native_currency: NativeCurrency {
+                name: "Ether".to_string(),
+                symbol: "ETH".to_string(),
+                decimals: 18,
+            },
+            rpc: vec![self.url(), self.ws_url()],
+            multicall: None,
+            max_batch_size: None,
+            max_log_range: None,
+            max_log_concurrency: None,
+            ens_registry: None,
+            l2_fee_model: None,
+            retry: None,
+            rate_limit: None,
+            explorer_rate_limit: None,
+            explorer: Some(ExplorerConfig::Blockscout {
+                url: self.url(),
+                api_key: None,
+            }),
+        }
+    }
+}
+
+impl Drop for MockChain {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
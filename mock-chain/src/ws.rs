@@ -0,0 +1,197 @@
+//! The WebSocket JSON-RPC endpoint served by [`MockChain`](crate::MockChain).
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use alloy::{primitives::U64, rpc::types::Filter};
+use axum::{
+    Extension,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use serde_json::{Value, json};
+use tokio::{sync::watch, time::interval};
+
+use crate::{fixture::Fixture, rpc};
+
+/// Interval between two notifications of a subscription.
+const NOTIFICATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The WebSocket connections of a [`MockChain`](crate::MockChain).
+#[derive(Debug)]
+pub(crate) struct Connections {
+    /// Number of connections accepted so far
+    accepted: AtomicUsize,
+    /// Bumped to close every open connection
+    drops: watch::Sender<u64>,
+}
+
+impl Connections {
+    /// Returns the number of connections accepted so far.
+    pub(crate) fn accepted(&self) -> usize {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Closes every open connection.
+    pub(crate) fn drop_all(&self) {
+        self.drops.send_modify(|drops| *drops += 1);
+    }
+}
+
+impl Default for Connections {
+    fn default() -> Self {
+        Self {
+            accepted: AtomicUsize::new(0),
+            drops: watch::Sender::new(0),
+        }
+    }
+}
+
+/// What a subscription is notified of.
+enum SubscriptionKind {
+    /// Fixture `blocks`, in a loop
+    NewHeads,
+    /// Fixture `logs` matching the filter, in a loop
+    Logs(Box<Filter>),
+}
+
+/// An `eth_subscribe` subscription of a connection.
+struct Subscription {
+    id: U64,
+    kind: SubscriptionKind,
+    /// Index of the next fixture entry to notify
+    cursor: usize,
+}
+
+/// `GET /`: upgrades to a WebSocket serving the same methods as `POST /`, plus
+/// `eth_subscribe` and `eth_unsubscribe`.
+pub(crate) async fn handle(
+    State(fixture): State<Arc<Fixture>>,
+    Extension(connections): Extension<Arc<Connections>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| {
+        connections.accepted.fetch_add(1, Ordering::Relaxed);
+        serve(socket, fixture, connections.drops.subscribe())
+    })
+}
+
+/// Serves a connection until the client closes it or `drops` changes.
+async fn serve(mut socket: WebSocket, fixture: Arc<Fixture>, mut drops: watch::Receiver<u64>) {
+    let mut subscriptions: Vec<Subscription> = Vec::new();
+    let mut next_id = 1;
+    let mut ticks = interval(NOTIFICATION_INTERVAL);
+
+    loop {
+        let replies = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let body = serde_json::from_str(&text).unwrap_or(Value::Null);
+                    let mut answer = |request: &Value| {
+                        respond(&fixture, &mut subscriptions, &mut next_id, request)
+                    };
+                    vec![match body {
+                        Value::Array(requests) => requests.iter().map(&mut answer).collect(),
+                        request => answer(&request),
+                    }]
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            _ = ticks.tick() => subscriptions
+                .iter_mut()
+                .filter_map(|subscription| notify(&fixture, subscription))
+                .collect(),
+            // dropped without a close frame, like a node going away
+            _ = drops.changed() => return,
+        };
+
+        for reply in replies {
+            if socket.send(Message::text(reply.to_string())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Answers a request, handling subscription methods and dispatching the others to
+/// [`rpc::respond`].
+fn respond(
+    fixture: &Fixture,
+    subscriptions: &mut Vec<Subscription>,
+    next_id: &mut u64,
+    request: &Value,
+) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let params = request.get("params").cloned().unwrap_or(json!([]));
+    let result = match request.get("method").and_then(Value::as_str) {
+        Some("eth_subscribe") => {
+            let kind = match params.get(0).and_then(Value::as_str) {
+                Some("newHeads") => Some(SubscriptionKind::NewHeads),
+                Some("logs") => serde_json::from_value(params.get(1).cloned().unwrap_or_default())
+                    .ok()
+                    .map(|filter| SubscriptionKind::Logs(Box::new(filter))),
+                _ => None,
+            };
+            let Some(kind) = kind else {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32602,
+                        "message": "invalid params: unsupported subscription",
+                    }
+                });
+            };
+
+            let subscription = U64::from(*next_id);
+            *next_id += 1;
+            subscriptions.push(Subscription {
+                id: subscription,
+                kind,
+                cursor: 0,
+            });
+            json!(subscription)
+        }
+        Some("eth_unsubscribe") => {
+            let (subscription,): (U64,) = serde_json::from_value(params).unwrap_or_default();
+            let count = subscriptions.len();
+            subscriptions.retain(|existing| existing.id != subscription);
+            json!(subscriptions.len() < count)
+        }
+        _ => return rpc::respond(fixture, request),
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Builds the next notification of `subscription`, or `None` if the fixture has nothing to
+/// notify it of.
+fn notify(fixture: &Fixture, subscription: &mut Subscription) -> Option<Value> {
+    let entries: Vec<Value> = match &subscription.kind {
+        SubscriptionKind::NewHeads => fixture.blocks.clone(),
+        SubscriptionKind::Logs(filter) => fixture
+            .logs
+            .iter()
+            .filter(|log| filter.rpc_matches(log))
+            .map(|log| json!(log))
+            .collect(),
+    };
+    let result = entries
+        .get(subscription.cursor % entries.len().max(1))?
+        .clone();
+    subscription.cursor += 1;
+
+    Some(json!({
+        "jsonrpc": "2.0",
+        "method": "eth_subscription",
+        "params": { "subscription": subscription.id, "result": result },
+    }))
+}
@@ -0,0 +1,102 @@
+//! Reads transaction history and ABIs from the mock explorer through a Blockscout source.
+
+use alloy::primitives::{Address, U64, U256, address};
+use foundry_block_explorers::account::{Sort, TxListParams};
+use mock_chain::{Fixture, MockChain};
+use multichain_client::{BlockscoutSource, ClientError, RateLimit, RetryPolicy, TransactionSource};
+
+const WALLET: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+/// The verified contract of the fixture
+const VERIFIED: Address = address!("0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+async fn start_mock() -> MockChain {
+    let fixture =
+        Fixture::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dev.json")).unwrap();
+    MockChain::start(fixture).await.unwrap()
+}
+
+/// Constructs a source for the explorer served at `url`, without retries.
+fn blockscout(url: &str) -> BlockscoutSource {
+    BlockscoutSource::new(
+        url,
+        None,
+        RetryPolicy {
+            max_attempts: 1,
+            initial_backoff_ms: 0,
+            max_backoff_ms: 0,
+        },
+        RateLimit {
+            requests_per_second: 100,
+            burst: None,
+        },
+    )
+    .unwrap()
+}
+
+fn params(page: u64, offset: u64) -> TxListParams {
+    TxListParams {
+        start_block: 0,
+        end_block: u64::MAX,
+        sort: Sort::Desc,
+        page,
+        offset,
+    }
+}
+
+#[tokio::test]
+async fn lists_transactions_from_the_api_endpoint() {
+    let mock = start_mock().await;
+    // the `/api` endpoint is found with or without a trailing slash on the instance URL
+    let url = mock.url();
+    for url in [url.as_str(), url.trim_end_matches('/')] {
+        let source = blockscout(url);
+        assert_eq!(source.name(), "Blockscout");
+
+        let transactions = source.transactions(WALLET, params(1, 2)).await.unwrap();
+        assert_eq!(transactions.len(), 2);
+        let newest = &transactions[0];
+        assert_eq!(newest.block_number.as_number(), Some(U64::from(1000)));
+        assert_eq!(newest.from.value(), Some(&WALLET));
+        assert_eq!(newest.gas_used, U256::from(48_210));
+        assert_eq!(
+            newest.method_id.as_ref().map(|id| id.to_string()),
+            Some("0x2f4f21e2".to_string())
+        );
+
+        let older = source.transactions(WALLET, params(2, 2)).await.unwrap();
+        assert_eq!(older.len(), 1);
+        assert_eq!(older[0].value, U256::from(1_500_000_000_000_000_000u64));
+    }
+}
+
+#[tokio::test]
+async fn lists_internal_transactions() {
+    let mock = start_mock().await;
+    let source = blockscout(&mock.url());
+
+    let internal = source
+        .internal_transactions(WALLET, params(1, 10))
+        .await
+        .unwrap();
+    let trace_ids: Vec<_> = internal.iter().map(|tx| tx.trace_id.as_str()).collect();
+    assert_eq!(trace_ids, ["0_1", "0_0"]);
+    assert_eq!(internal[0].value, U256::from(300_000_000_000_000_000u64));
+}
+
+#[tokio::test]
+async fn missing_history_and_unverified_sources() {
+    let mock = start_mock().await;
+    let source = blockscout(&mock.url());
+
+    // "No transactions found" carries an empty list, which is not an error
+    let transactions = source
+        .transactions(Address::ZERO, params(1, 10))
+        .await
+        .unwrap();
+    assert!(transactions.is_empty());
+
+    let abi = source.contract_abi(VERIFIED).await.unwrap();
+    assert!(abi.function("depositFor").is_some());
+    let err = source.contract_abi(WALLET).await.unwrap_err();
+    assert!(matches!(err, ClientError::NotFound(_)), "{err}");
+}
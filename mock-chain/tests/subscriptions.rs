@@ -0,0 +1,51 @@
+//! Subscribes to a mock chain over WebSocket and drops the connection under the client.
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use mock_chain::{Fixture, MockChain};
+use tokio::time::timeout;
+
+/// Longest wait for a notification, covering the mock's one-second interval and reconnecting.
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+async fn start_mock() -> MockChain {
+    let fixture =
+        Fixture::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/dev.json")).unwrap();
+    MockChain::start(fixture).await.unwrap()
+}
+
+#[tokio::test]
+async fn requests_and_subscriptions_share_a_connection() {
+    let mock = start_mock().await;
+    let mut metadata = mock.chain_metadata("Mock Devnet", "dev");
+    metadata.rpc = vec![mock.ws_url()];
+    let client = metadata.create_rpc_client("").unwrap();
+
+    let mut heads = Box::pin(client.subscribe_new_heads().unwrap());
+    timeout(NOTIFICATION_TIMEOUT, heads.next())
+        .await
+        .expect("no head before the connection dropped");
+    client.get_block_number().await.unwrap();
+    assert_eq!(mock.ws_connections(), 1);
+}
+
+#[tokio::test]
+async fn subscriptions_resume_after_the_connection_drops() {
+    let mock = start_mock().await;
+    let mut metadata = mock.chain_metadata("Mock Devnet", "dev");
+    metadata.rpc = vec![mock.ws_url()];
+    let client = metadata.create_rpc_client("").unwrap();
+
+    let mut heads = Box::pin(client.subscribe_new_heads().unwrap());
+    timeout(NOTIFICATION_TIMEOUT, heads.next())
+        .await
+        .expect("no head before the connection dropped");
+
+    mock.drop_ws_connections();
+    timeout(NOTIFICATION_TIMEOUT, heads.next())
+        .await
+        .expect("the subscription did not resume");
+    assert!(mock.ws_connections() > 1);
+    client.get_block_number().await.unwrap();
+}
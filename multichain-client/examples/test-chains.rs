@@ -32,7 +32,7 @@ fn create_clients(
     let mut client_map = HashMap::new();
     for chain in chains.iter() {
         match chain.create_rpc_client(etherscan_api_key) {
-            Ok(mut client) => {
+            Ok(client) => {
                 debug!("✅ Created client for {}", chain.name);
                 let path_str = format!("{}-tokens.json", chain.short_name);
                 let path = Path::new(&path_str);
@@ -0,0 +1,181 @@
+//! On-disk cache of verified contract ABIs.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use alloy::{json_abi::JsonAbi, primitives::Address};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::error::{ClientError, Result};
+
+/// How long a contract is remembered as unverified before the explorer is asked again.
+const UNVERIFIED_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long the implementation behind a contract is remembered before its storage is read
+/// again, since proxies can be upgraded.
+const IMPLEMENTATION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Name of the directory holding the implementations of cached contracts.
+const IMPLEMENTATIONS_DIR: &str = "implementations";
+
+/// A directory of verified contract ABIs, shared by every chain.
+///
+/// Each ABI is stored as it was returned by the explorer, at `{dir}/{chain_id}/{address}.json`.
+/// Contracts without verified source are stored as `null`, so they are not looked up again
+/// for a day. The implementation behind each contract, or `null` if it is not a proxy, is stored
+/// at `{dir}/implementations/{chain_id}/{address}.json` and read again after a day.
+#[derive(Clone, Debug)]
+pub struct AbiCache {
+    dir: PathBuf,
+}
+
+impl AbiCache {
+    /// Constructs a new `AbiCache` in `dir`, which is created on the first write.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Looks up the ABI of `address` on the chain with ID `chain_id`.
+    ///
+    /// Returns `None` on a cache miss, and `Some(None)` if the contract is known to be
+    /// unverified.
+    pub fn get(&self, chain_id: u64, address: Address) -> Option<Option<JsonAbi>> {
+        let path = self.path(chain_id, address);
+        let data = fs::read_to_string(&path).ok()?;
+        let abi: Option<JsonAbi> = match serde_json::from_str(&data) {
+            Ok(abi) => abi,
+            Err(err) => {
+                warn!("Ignoring invalid cached ABI {}: {err}", path.display());
+                return None;
+            }
+        };
+
+        if abi.is_none() && age(&path)? > UNVERIFIED_TTL {
+            return None;
+        }
+        Some(abi)
+    }
+
+    /// Stores the ABI of `address`, or `None` to record that the contract is unverified.
+    pub fn insert(&self, chain_id: u64, address: Address, abi: Option<&JsonAbi>) -> Result<()> {
+        write(&self.path(chain_id, address), &abi)
+    }
+
+    /// Looks up the implementation behind `address` on the chain with ID `chain_id`.
+    ///
+    /// Returns `None` on a cache miss or once the entry is a day old, and `Some(None)` if the
+    /// contract is known not to be a proxy.
+    pub fn get_implementation(&self, chain_id: u64, address: Address) -> Option<Option<Address>> {
+        let path = self.implementation_path(chain_id, address);
+        if age(&path)? > IMPLEMENTATION_TTL {
+            return None;
+        }
+        let data = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&data)
+            .inspect_err(|err| {
+                warn!(
+                    "Ignoring invalid cached implementation {}: {err}",
+                    path.display()
+                )
+            })
+            .ok()
+    }
+
+    /// Stores the implementation behind `address`, or `None` to record that it is not a proxy.
+    pub fn insert_implementation(
+        &self,
+        chain_id: u64,
+        address: Address,
+        implementation: Option<Address>,
+    ) -> Result<()> {
+        write(
+            &self.implementation_path(chain_id, address),
+            &implementation,
+        )
+    }
+
+    /// Returns every cached ABI of every chain with the chain ID and address of its contract,
+    /// skipping unreadable files.
+    ///
+    /// Proxies are listed a second time with the ABI of their implementation, after every
+    /// contract's own ABI, as long as both the implementation and its ABI are cached.
+    pub fn abis(&self) -> Vec<(u64, Address, JsonAbi)> {
+        let mut abis: Vec<_> = cached_files(&self.dir)
+            .filter_map(|(chain_id, address, data)| {
+                let abi = serde_json::from_str::<Option<JsonAbi>>(&data).ok()??;
+                Some((chain_id, address, abi))
+            })
+            .collect();
+
+        let implementations: Vec<_> = cached_files(&self.dir.join(IMPLEMENTATIONS_DIR))
+            .filter_map(|(chain_id, address, data)| {
+                let implementation = serde_json::from_str::<Option<Address>>(&data).ok()??;
+                let abi = abis.iter().find_map(|(id, contract, abi)| {
+                    (*id == chain_id && *contract == implementation).then(|| abi.clone())
+                })?;
+                Some((chain_id, address, abi))
+            })
+            .collect();
+        abis.extend(implementations);
+        abis
+    }
+
+    fn path(&self, chain_id: u64, address: Address) -> PathBuf {
+        self.dir
+            .join(chain_id.to_string())
+            .join(format!("{address:#x}.json"))
+    }
+
+    fn implementation_path(&self, chain_id: u64, address: Address) -> PathBuf {
+        self.dir
+            .join(IMPLEMENTATIONS_DIR)
+            .join(chain_id.to_string())
+            .join(format!("{address:#x}.json"))
+    }
+}
+
+/// Returns the time since `path` was last written, or `None` if it cannot be read.
+fn age(path: &Path) -> Option<Duration> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    SystemTime::now().duration_since(modified).ok()
+}
+
+/// Writes `value` as JSON to `path`, creating its directory.
+fn write(path: &Path, value: &impl Serialize) -> Result<()> {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(value)?)
+    };
+    write().map_err(|e| ClientError::Config(format!("Failed to write {}: {e}", path.display())))
+}
+
+/// Reads every `{chain_id}/{address}.json` file under `dir`, skipping other entries.
+fn cached_files(dir: &Path) -> impl Iterator<Item = (u64, Address, String)> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|chain| {
+            let chain_id = chain.file_name().to_str()?.parse::<u64>().ok()?;
+            Some((chain_id, fs::read_dir(chain.path()).ok()?))
+        })
+        .flat_map(|(chain_id, entries)| {
+            entries.flatten().filter_map(move |entry| {
+                let path = entry.path();
+                let address = path.file_stem()?.to_str()?.parse::<Address>().ok()?;
+                let data = fs::read_to_string(&path).ok()?;
+                Some((chain_id, address, data))
+            })
+        })
+}
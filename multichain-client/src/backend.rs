@@ -0,0 +1,83 @@
+//! The JSON-RPC backends an [`EvmChainClient`](crate::EvmChainClient) can send requests through.
+//!
+//! Production clients use an [`EndpointPool`](crate::EndpointPool) of HTTP and [`WsBackend`]
+//! WebSocket endpoints, while [`ScriptedBackend`] answers from in-memory scripted responses so
+//! client logic can be exercised offline.
+
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{
+        TransportError, TransportFut,
+        http::{Http, reqwest::Client as HttpClient},
+    },
+};
+use tower::Service;
+
+mod scripted;
+mod ws;
+
+pub use scripted::{ScriptedBackend, ScriptedBatch, ScriptedResponse};
+pub use ws::WsBackend;
+pub(crate) use ws::WsConnection;
+
+/// A JSON-RPC backend for a single chain.
+///
+/// Implementations receive the requests of an [`EvmChainClient`](crate::EvmChainClient) after
+/// retries and rate limiting have been applied, and must answer batches with one response per
+/// request.
+pub trait ChainBackend: fmt::Debug + Send + Sync + 'static {
+    /// Sends a single request or a batch and returns the response.
+    fn send(&self, request: RequestPacket) -> TransportFut<'static>;
+
+    /// Returns `true` if the backend already retries transport errors on other endpoints, so
+    /// retrying them again would only multiply the attempts.
+    fn fails_over(&self) -> bool {
+        false
+    }
+}
+
+impl ChainBackend for Http<HttpClient> {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        self.clone().call(request)
+    }
+}
+
+impl<B: ChainBackend + ?Sized> ChainBackend for Arc<B> {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        (**self).send(request)
+    }
+
+    fn fails_over(&self) -> bool {
+        (**self).fails_over()
+    }
+}
+
+/// Adapts a [`ChainBackend`] to the alloy transport interface.
+#[derive(Clone, Debug)]
+pub(crate) struct BackendTransport(Arc<dyn ChainBackend>);
+
+impl BackendTransport {
+    pub(crate) fn new(backend: impl ChainBackend) -> Self {
+        Self(Arc::new(backend))
+    }
+}
+
+impl Service<RequestPacket> for BackendTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        self.0.send(request)
+    }
+}
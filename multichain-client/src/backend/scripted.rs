@@ -0,0 +1,249 @@
+//! An in-memory [`ChainBackend`] that answers from scripted responses.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use alloy::{
+    rpc::json_rpc::{
+        ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+    },
+    transports::{TransportError, TransportErrorKind, TransportFut},
+};
+use serde::Serialize;
+use serde_json::{Value, value::to_raw_value};
+
+use super::ChainBackend;
+use crate::batch::RpcCall;
+
+/// A scripted answer to a JSON-RPC request.
+#[derive(Clone, Debug)]
+pub enum ScriptedResponse {
+    /// A successful response with the given result
+    Result(Value),
+
+    /// A JSON-RPC error response
+    Error {
+        /// JSON-RPC error code (e.g. `3` for reverted calls)
+        code: i64,
+        /// Error message
+        message: String,
+    },
+
+    /// A transport failure, as if the endpoint could not be reached
+    ///
+    /// Fails the whole packet, including the other requests of a batch.
+    TransportError(String),
+}
+
+impl ScriptedResponse {
+    /// Constructs a successful response, serializing `result` to JSON.
+    ///
+    /// # Panics
+    /// Panics if `result` cannot be serialized.
+    pub fn result(result: impl Serialize) -> Self {
+        Self::Result(serde_json::to_value(result).expect("scripted result must serialize"))
+    }
+
+    /// Constructs a JSON-RPC error response.
+    pub fn error(code: i64, message: impl Into<String>) -> Self {
+        Self::Error {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A scripted answer to a whole JSON-RPC batch, applied before its requests are answered.
+#[derive(Clone, Debug)]
+pub enum ScriptedBatch {
+    /// Rejects the batch with a transport error, as endpoints without batch support do
+    Reject(String),
+
+    /// Answers only the first requests of the batch, up to the given number
+    Truncate(usize),
+
+    /// Answers every request of the batch, in reverse order
+    Reverse,
+}
+
+type Handler = Arc<dyn Fn(&Value) -> ScriptedResponse + Send + Sync>;
+
+#[derive(Default)]
+struct Script {
+    handlers: HashMap<String, Handler>,
+    queued: HashMap<String, VecDeque<ScriptedResponse>>,
+    batch: Option<ScriptedBatch>,
+    requests: Vec<RpcCall>,
+    batches: Vec<usize>,
+}
+
+/// A [`ChainBackend`] that answers requests from responses scripted per JSON-RPC method.
+///
+/// One-shot responses registered with [`Self::once`] are used first, in order, then the handler
+/// registered with [`Self::on`] or [`Self::on_request`]. Methods without a script answer with a
+/// "method not found" error. Batches are answered request by request unless scripted with
+/// [`Self::on_batch`]. Every answered request is recorded and can be inspected with
+/// [`Self::requests`], and the size of every batch with [`Self::batches`].
+///
+/// Clones share the same script, so a backend can be kept around to inspect it after handing a
+/// clone to a client.
+#[derive(Clone, Default)]
+pub struct ScriptedBackend(Arc<Mutex<Script>>);
+
+impl ScriptedBackend {
+    /// Constructs an empty `ScriptedBackend`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every request for `method` with `response`.
+    pub fn on(&self, method: &str, response: ScriptedResponse) -> &Self {
+        self.on_request(method, move |_| response.clone())
+    }
+
+    /// Answers every request for `method` with the response computed by `handler` from the
+    /// request params.
+    pub fn on_request(
+        &self,
+        method: &str,
+        handler: impl Fn(&Value) -> ScriptedResponse + Send + Sync + 'static,
+    ) -> &Self {
+        self.script()
+            .handlers
+            .insert(method.to_string(), Arc::new(handler));
+        self
+    }
+
+    /// Answers the next request for `method` with `response`, before falling back to the
+    /// handler registered for it.
+    pub fn once(&self, method: &str, response: ScriptedResponse) -> &Self {
+        self.script()
+            .queued
+            .entry(method.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Answers every batch according to `batch`.
+    pub fn on_batch(&self, batch: ScriptedBatch) -> &Self {
+        self.script().batch = Some(batch);
+        self
+    }
+
+    /// Returns every request answered so far, in order.
+    pub fn requests(&self) -> Vec<RpcCall> {
+        self.script().requests.clone()
+    }
+
+    /// Returns the number of requests in every batch received so far, in order.
+    pub fn batches(&self) -> Vec<usize> {
+        self.script().batches.clone()
+    }
+
+    fn script(&self) -> std::sync::MutexGuard<'_, Script> {
+        self.0.lock().expect("scripted backend lock poisoned")
+    }
+
+    /// Records `request` and returns its scripted response.
+    fn respond(&self, request: &SerializedRequest) -> ScriptedResponse {
+        let method = request.method();
+        let params = request
+            .params()
+            .and_then(|params| serde_json::from_str(params.get()).ok())
+            .unwrap_or(Value::Null);
+
+        let handler = {
+            let mut script = self.script();
+            script
+                .requests
+                .push(RpcCall::new(Cow::Owned(method.to_string()), params.clone()));
+            if let Some(response) = script.queued.get_mut(method).and_then(VecDeque::pop_front) {
+                return response;
+            }
+            script.handlers.get(method).cloned()
+        };
+
+        match handler {
+            Some(handler) => handler(&params),
+            None => ScriptedResponse::error(
+                -32601,
+                format!("the method {method} does not exist/is not available"),
+            ),
+        }
+    }
+
+    fn response(&self, request: &SerializedRequest) -> Result<Response, TransportError> {
+        let payload = match self.respond(request) {
+            ScriptedResponse::Result(result) => {
+                ResponsePayload::Success(to_raw_value(&result).map_err(TransportErrorKind::custom)?)
+            }
+            ScriptedResponse::Error { code, message } => ResponsePayload::Failure(ErrorPayload {
+                code,
+                message: message.into(),
+                data: None,
+            }),
+            ScriptedResponse::TransportError(message) => {
+                return Err(TransportErrorKind::custom_str(&message));
+            }
+        };
+
+        Ok(Response {
+            id: request.id().clone(),
+            payload,
+        })
+    }
+
+    fn batch_response(
+        &self,
+        requests: &[SerializedRequest],
+    ) -> Result<Vec<Response>, TransportError> {
+        let batch = {
+            let mut script = self.script();
+            script.batches.push(requests.len());
+            script.batch.clone()
+        };
+
+        let answered = match &batch {
+            Some(ScriptedBatch::Reject(message)) => {
+                return Err(TransportErrorKind::custom_str(message));
+            }
+            Some(ScriptedBatch::Truncate(len)) => &requests[..requests.len().min(*len)],
+            Some(ScriptedBatch::Reverse) | None => requests,
+        };
+        let mut responses = answered
+            .iter()
+            .map(|request| self.response(request))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(ScriptedBatch::Reverse) = batch {
+            responses.reverse();
+        }
+        Ok(responses)
+    }
+}
+
+impl fmt::Debug for ScriptedBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let script = self.script();
+        f.debug_struct("ScriptedBackend")
+            .field("methods", &script.handlers.keys().collect::<Vec<_>>())
+            .field("requests", &script.requests.len())
+            .finish()
+    }
+}
+
+impl ChainBackend for ScriptedBackend {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        let response = match request {
+            RequestPacket::Single(request) => self.response(&request).map(ResponsePacket::Single),
+            RequestPacket::Batch(requests) => {
+                self.batch_response(&requests).map(ResponsePacket::Batch)
+            }
+        };
+        Box::pin(async move { response })
+    }
+}
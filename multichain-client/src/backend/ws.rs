@@ -0,0 +1,108 @@
+//! A [`ChainBackend`] that sends requests over a WebSocket connection.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use alloy::{
+    pubsub::{PubSubConnect, PubSubFrontend},
+    rpc::{client::RpcClient, json_rpc::RequestPacket},
+    transports::{RpcError, TransportError, TransportErrorKind, TransportFut, ws::WsConnect},
+};
+use tokio::{sync::Mutex, time::timeout};
+
+use crate::backend::ChainBackend;
+
+/// Timeout applied to opening the connection and to every request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of attempts made to re-establish a dropped connection before giving up on it.
+const MAX_RECONNECTS: u32 = 5;
+
+/// Delay between attempts to re-establish a dropped connection.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A JSON-RPC backend for a `ws://` or `wss://` endpoint.
+///
+/// The connection is opened on the first request. A dropped connection is re-established in
+/// the background, re-sending in-flight requests and re-creating active subscriptions; once
+/// that fails, the next request opens a new connection.
+#[derive(Clone)]
+pub struct WsBackend {
+    url: Arc<str>,
+    connection: Arc<Mutex<Option<WsConnection>>>,
+}
+
+/// An open connection to a WebSocket endpoint.
+#[derive(Clone)]
+pub(crate) struct WsConnection {
+    /// Frontend of the task driving the connection
+    pub(crate) frontend: PubSubFrontend,
+    /// Client for requests sent by the crate itself, shared so request IDs stay unique on the
+    /// connection
+    pub(crate) client: RpcClient,
+}
+
+impl WsBackend {
+    /// Constructs a new `WsBackend` for `url`, without connecting.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.into(),
+            connection: Default::default(),
+        }
+    }
+
+    /// Returns the URL of the endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the current connection, opening one if there is none.
+    pub(crate) async fn connection(&self) -> Result<WsConnection, TransportError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+
+        let connect = WsConnect::new(self.url.to_string())
+            .with_max_retries(MAX_RECONNECTS)
+            .with_retry_interval(RECONNECT_INTERVAL);
+        let frontend = timeout(REQUEST_TIMEOUT, connect.into_service())
+            .await
+            .map_err(|_| TransportErrorKind::custom_str("WebSocket connection timed out"))??;
+        let client = RpcClient::new(frontend.clone(), false);
+        Ok(connection.insert(WsConnection { frontend, client }).clone())
+    }
+
+    /// Drops the current connection, so the next request opens a new one.
+    pub(crate) async fn disconnect(&self) {
+        self.connection.lock().await.take();
+    }
+}
+
+impl fmt::Debug for WsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WsBackend")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChainBackend for WsBackend {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        let backend = self.clone();
+        Box::pin(async move {
+            let connection = backend.connection().await?;
+            let response = timeout(REQUEST_TIMEOUT, connection.frontend.send_packet(request))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(TransportErrorKind::custom_str(
+                        "WebSocket request timed out",
+                    ))
+                });
+            // the connection could not be re-established
+            if let Err(RpcError::Transport(TransportErrorKind::BackendGone)) = &response {
+                backend.disconnect().await;
+            }
+            response
+        })
+    }
+}
@@ -0,0 +1,43 @@
+//! Types used to send JSON-RPC batch requests through an [`EvmChainClient`](crate::EvmChainClient).
+
+use std::borrow::Cow;
+
+use alloy::primitives::{Bytes, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Number of requests sent per JSON-RPC batch when a chain does not configure `maxBatchSize`.
+pub(crate) const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// A single call to be sent as part of a JSON-RPC batch.
+#[derive(Clone, Debug)]
+pub struct RpcCall {
+    /// JSON-RPC method name (e.g. `eth_getBalance`)
+    pub method: Cow<'static, str>,
+
+    /// Positional parameters for the method
+    pub params: Value,
+}
+
+impl RpcCall {
+    /// Constructs a new `RpcCall` from a method name and its parameters.
+    pub fn new(method: impl Into<Cow<'static, str>>, params: Value) -> Self {
+        Self {
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// Basic on-chain state of an address, fetched in a single batch.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AccountState {
+    /// Native balance of the address
+    pub balance: U256,
+
+    /// Number of transactions sent from the address
+    pub nonce: u64,
+
+    /// Deployed bytecode, empty for externally owned accounts
+    pub code: Bytes,
+}
@@ -1,59 +1,130 @@
 //! Defines the `EvmChainClient` and `EvmChainClients` types for interacting with EVM-compatible blockchains.
 //!
-//! Includes methods for fetching native and token balances, transactions, and dynamically extending token metadata.
+//! Includes methods for fetching native and token balances, normal and internal transactions, and dynamically extending token metadata.
 
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+};
 
 use alloy::{
+    eips::BlockId,
     hex,
-    primitives::{Address, U256},
-    rpc::client::ReqwestClient,
+    primitives::{Address, Bytes, U64, U256},
+    rpc::{
+        client::{ClientBuilder, ReqwestClient},
+        json_rpc::RpcRecv,
+    },
     sol,
     sol_types::SolCall,
+    transports::{RpcError, TransportErrorKind},
 };
-use anyhow::{Error, Result};
-use foundry_block_explorers::{
-    Client as EtherscanClient,
-    account::{NormalTransaction, Sort, TxListParams},
+use foundry_block_explorers::account::{
+    InternalTransaction, NormalTransaction, Sort, TxListParams,
 };
 use futures::future::{join_all, try_join_all};
+use serde_json::{Value, json};
 use tracing::{info, warn};
 
+use self::{blocks::BlockTimeCache, ens::EnsNameCache, nfts::TokenIdScanCache};
 use crate::{
     ChainMetaData,
-    metadata::{NativeCurrency, TokenBalance, TokenMetadata},
+    abi::AbiCache,
+    backend::{BackendTransport, ChainBackend},
+    batch::{AccountState, DEFAULT_BATCH_SIZE, RpcCall},
+    error::{ClientError, Result},
+    history::TransactionSource,
+    metadata::{
+        NativeCurrency, NftCollectionMetadata, TokenBalance, TokenBalanceResult, TokenMetadata,
+    },
+    multicall::{Call3, MULTICALL_CHUNK_SIZE, aggregate3Call},
+    rate_limit::RateLimiter,
+    retry::RetryLayer,
+    subscription::Subscriptions,
 };
 
+mod accounts;
+mod blocks;
+mod contracts;
+mod ens;
+mod erc1155;
+mod logs;
+mod nfts;
+mod receipts;
+mod subscriptions;
+mod tokens;
+mod transfers;
+
 sol! {
     function balanceOf(address) external view returns (uint256);
 }
 
 /// A client for a single EVM-compatible chain.
-/// Holds RPC and Etherscan clients, native currency metadata, and token metadata.
+/// Holds the RPC client, a transaction history source, native currency metadata, and token metadata.
 #[derive(Clone)]
 pub struct EvmChainClient {
     metadata: ChainMetaData,
     rpc_client: Arc<ReqwestClient>,
-    etherscan: Arc<EtherscanClient>,
-    tokens: Vec<TokenMetadata>,
+    transaction_source: Arc<dyn TransactionSource>,
+    tokens: Arc<RwLock<Arc<[TokenMetadata]>>>,
+    nft_collections: Arc<RwLock<Vec<NftCollectionMetadata>>>,
+    block_times: Arc<Mutex<BlockTimeCache>>,
+    ens_names: Arc<Mutex<EnsNameCache>>,
+    token_id_scans: Arc<Mutex<TokenIdScanCache>>,
+    abi_cache: Option<AbiCache>,
+    subscriptions: Option<Subscriptions>,
 }
 
 impl EvmChainClient {
     /// Constructs a new `EvmChainClient`.
+    ///
+    /// Transaction history is read from `transaction_source`, which is responsible for its own
+    /// rate limiting and retries.
     pub fn new(
         metadata: ChainMetaData,
         rpc_client: Arc<ReqwestClient>,
-        etherscan: Arc<EtherscanClient>,
+        transaction_source: Arc<dyn TransactionSource>,
         tokens: Vec<TokenMetadata>,
     ) -> Self {
         Self {
             metadata,
             rpc_client,
-            etherscan,
-            tokens,
+            transaction_source,
+            tokens: Arc::new(RwLock::new(tokens.into())),
+            nft_collections: Default::default(),
+            block_times: Default::default(),
+            ens_names: Default::default(),
+            token_id_scans: Default::default(),
+            abi_cache: None,
+            subscriptions: None,
         }
     }
 
+    /// Constructs a new `EvmChainClient` that sends its RPC requests through `backend`.
+    ///
+    /// Requests are retried and rate limited according to the `retry` and `rateLimit` settings
+    /// of `metadata` before reaching the backend. Transport errors are not retried when the
+    /// backend [fails over](ChainBackend::fails_over) to other endpoints on its own.
+    pub fn with_backend(
+        metadata: ChainMetaData,
+        backend: impl ChainBackend,
+        transaction_source: Arc<dyn TransactionSource>,
+        tokens: Vec<TokenMetadata>,
+    ) -> Self {
+        let retry = RetryLayer::new(
+            metadata.retry.unwrap_or_default(),
+            metadata.rate_limit.map(RateLimiter::new),
+        )
+        .with_transport_retries(!backend.fails_over());
+        let rpc_client = ClientBuilder::default()
+            .layer(retry)
+            .transport(BackendTransport::new(backend), false);
+
+        Self::new(metadata, Arc::new(rpc_client), transaction_source, tokens)
+    }
+
     /// Returns the chain [`ChainMetaData`].
     pub fn metadata(&self) -> &ChainMetaData {
         &self.metadata
@@ -64,14 +135,29 @@ impl EvmChainClient {
         &self.rpc_client
     }
 
-    /// Returns the Etherscan client for fetching transactions.
-    pub fn etherscan(&self) -> &Arc<EtherscanClient> {
-        &self.etherscan
+    /// Returns the source used for fetching transaction history.
+    pub fn transaction_source(&self) -> &Arc<dyn TransactionSource> {
+        &self.transaction_source
     }
 
-    /// Returns the list of tracked tokens for the chain.
-    pub fn tokens(&self) -> &[TokenMetadata] {
-        &self.tokens
+    /// Returns a snapshot of the tracked tokens for the chain.
+    ///
+    /// The list is shared by every clone of the client, so tokens added at runtime are visible
+    /// everywhere. Adding a token replaces the list rather than modifying it, so snapshots are
+    /// cheap to take and never change.
+    pub fn tokens(&self) -> Arc<[TokenMetadata]> {
+        self.tokens
+            .read()
+            .expect("token list lock poisoned")
+            .clone()
+    }
+
+    /// Returns the list of tracked ERC-721 collections for the chain.
+    pub fn nft_collections(&self) -> Vec<NftCollectionMetadata> {
+        self.nft_collections
+            .read()
+            .expect("NFT collection list lock poisoned")
+            .clone()
     }
 
     /// Returns the native currency metadata (e.g., ETH or MATIC).
@@ -83,64 +169,320 @@ impl EvmChainClient {
     ///
     /// The file should be formatted as an array of:
     /// `[{ "address": "0x...", "name": "Token", "symbol": "SYM", "decimals": 18 }]`
-    pub fn add_tokens_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        let data = fs::read_to_string(path)?;
-        let tokens: Vec<TokenMetadata> = serde_json::from_str(&data)?;
+    pub fn add_tokens_from_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| ClientError::Config(format!("Failed to read {}: {e}", path.display())))?;
+        let tokens: Vec<TokenMetadata> = serde_json::from_str(&data).map_err(|e| {
+            ClientError::Config(format!("Invalid token list {}: {e}", path.display()))
+        })?;
+
+        let mut tracked = self.tokens.write().expect("token list lock poisoned");
+        *tracked = tracked.iter().cloned().chain(tokens).collect();
+        Ok(())
+    }
 
-        self.tokens.extend_from_slice(&tokens);
+    /// Appends ERC-721 collections from a JSON file to the internal collection list.
+    ///
+    /// The file should be formatted as an array of:
+    /// `[{ "address": "0x...", "name": "Collection", "symbol": "SYM", "startBlock": 12287507 }]`
+    pub fn add_nft_collections_from_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| ClientError::Config(format!("Failed to read {}: {e}", path.display())))?;
+        let collections: Vec<NftCollectionMetadata> = serde_json::from_str(&data).map_err(|e| {
+            ClientError::Config(format!(
+                "Invalid NFT collection list {}: {e}",
+                path.display()
+            ))
+        })?;
+
+        self.nft_collections
+            .write()
+            .expect("NFT collection list lock poisoned")
+            .extend_from_slice(&collections);
         Ok(())
     }
 
     /// Fetches the native balance (ETH/MATIC/etc) of an address.
     pub async fn get_native_balance(&self, address: Address) -> Result<U256> {
-        match self
+        self.get_native_balance_at(address, BlockId::latest()).await
+    }
+
+    /// Fetches the native balance of an address at the given block.
+    pub async fn get_native_balance_at(&self, address: Address, block: BlockId) -> Result<U256> {
+        Ok(self
             .rpc_client
-            .request::<_, U256>("eth_getBalance", (address, "latest"))
-            .await
-        {
-            Ok(balance) => Ok(balance),
-            Err(e) => Err(anyhow::anyhow!("Failed to fetch native balance: {}", e)),
-        }
+            .request::<_, U256>("eth_getBalance", (address, block))
+            .await?)
     }
 
     /// Fetches the balance for each tracked token for the given address.
     ///
-    /// Only returns tokens with non-zero balances.
+    /// Only returns tokens with non-zero balances. Tokens whose balance could not be read are
+    /// logged and skipped; use [`Self::get_token_balance_results`] to inspect them.
     pub async fn get_token_balances(&self, address: Address) -> Vec<TokenBalance> {
-        let futures = self.tokens.iter().map(|token| {
-            let token = token.clone();
-            let client = self.rpc_client.clone();
-            let call_data = balanceOfCall(address).abi_encode();
+        self.get_token_balances_at(address, BlockId::latest()).await
+    }
 
-            let call = serde_json::json!({
-                "to": format!("{:?}", token.address),
-                "data": format!("0x{}", hex::encode(call_data)),
-            });
+    /// Fetches the balance for each tracked token for the given address at the given block.
+    ///
+    /// Only returns tokens with non-zero balances, like [`Self::get_token_balances`].
+    pub async fn get_token_balances_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Vec<TokenBalance> {
+        self.get_token_balance_results_at(address, block)
+            .await
+            .into_iter()
+            .filter_map(|TokenBalanceResult { token, balance }| match balance {
+                Ok(balance) if balance.is_zero() => None,
+                Ok(balance) => Some(TokenBalance { token, balance }),
+                Err(err) => {
+                    warn!("Error fetching balance for {}: {err}", token.symbol);
+                    None
+                }
+            })
+            .collect()
+    }
 
-            async move {
-                match client
-                    .request::<_, U256>("eth_call", (call, "latest"))
+    /// Reads the balance of every tracked token for the given address.
+    ///
+    /// If the chain has a Multicall3 address configured, balances are read in chunks through
+    /// `aggregate3`. Otherwise, or if an aggregated call fails, one `eth_call` is made per token.
+    ///
+    /// Returns one result per tracked token, in the same order as [`Self::tokens`].
+    pub async fn get_token_balance_results(&self, address: Address) -> Vec<TokenBalanceResult> {
+        self.get_token_balance_results_at(address, BlockId::latest())
+            .await
+    }
+
+    /// Reads the balance of every tracked token for the given address at the given block.
+    ///
+    /// See [`Self::get_token_balance_results`].
+    pub async fn get_token_balance_results_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Vec<TokenBalanceResult> {
+        let tokens = self.tokens();
+        match self.metadata.multicall {
+            Some(multicall) => {
+                let futures = tokens.chunks(MULTICALL_CHUNK_SIZE).map(|chunk| {
+                    self.get_token_balances_multicall(multicall, address, block, chunk)
+                });
+                join_all(futures).await.into_iter().flatten().collect()
+            }
+            None => {
+                self.get_token_balances_single(address, block, &tokens)
                     .await
-                {
-                    Ok(balance) => {
-                        if balance == U256::ZERO {
-                            None
-                        } else {
-                            Some(TokenBalance { token, balance })
-                        }
-                    }
-                    Err(err) => {
-                        tracing::warn!("Error fetching balance for {}: {err}", token.symbol);
-                        None
-                    }
+            }
+        }
+    }
+
+    /// Reads `tokens` balances with a single `aggregate3` call, falling back to per-token calls
+    /// if the multicall itself fails (e.g. the contract is not deployed on this chain, or not yet
+    /// deployed at `block`).
+    async fn get_token_balances_multicall(
+        &self,
+        multicall: Address,
+        address: Address,
+        block: BlockId,
+        tokens: &[TokenMetadata],
+    ) -> Vec<TokenBalanceResult> {
+        let call_data = Bytes::from(balanceOfCall(address).abi_encode());
+        let calls = tokens
+            .iter()
+            .map(|token| Call3 {
+                target: token.address,
+                allowFailure: true,
+                callData: call_data.clone(),
+            })
+            .collect();
+
+        let results = match self
+            .eth_call::<Bytes>(multicall, aggregate3Call { calls }.abi_encode(), block)
+            .await
+            .and_then(|data| Ok(aggregate3Call::abi_decode_returns(&data)?))
+        {
+            Ok(results) if results.len() == tokens.len() => results,
+            Ok(results) => {
+                warn!(
+                    "Multicall on {} returned {} results for {} calls, falling back to single calls",
+                    self.metadata.name,
+                    results.len(),
+                    tokens.len()
+                );
+                return self.get_token_balances_single(address, block, tokens).await;
+            }
+            Err(err) => {
+                warn!(
+                    "Multicall on {} failed, falling back to single calls: {err}",
+                    self.metadata.name
+                );
+                return self.get_token_balances_single(address, block, tokens).await;
+            }
+        };
+
+        tokens
+            .iter()
+            .zip(results)
+            .map(|(token, result)| {
+                let balance = if result.success {
+                    balanceOfCall::abi_decode_returns(&result.returnData).map_err(Into::into)
+                } else {
+                    Err(ClientError::Reverted(format!(
+                        "balanceOf on token {}",
+                        token.address
+                    )))
+                };
+                TokenBalanceResult {
+                    token: token.clone(),
+                    balance,
+                }
+            })
+            .collect()
+    }
+
+    /// Reads `tokens` balances with one `eth_call` per token.
+    async fn get_token_balances_single(
+        &self,
+        address: Address,
+        block: BlockId,
+        tokens: &[TokenMetadata],
+    ) -> Vec<TokenBalanceResult> {
+        let futures = tokens.iter().map(|token| {
+            let call_data = balanceOfCall(address).abi_encode();
+
+            async move {
+                let balance = self.eth_call::<U256>(token.address, call_data, block).await;
+                TokenBalanceResult {
+                    token: token.clone(),
+                    balance,
                 }
             }
         });
 
+        join_all(futures).await
+    }
+
+    /// Fetches the native balance, nonce and code of an address in a single batch.
+    pub async fn get_account_state(&self, address: Address) -> Result<AccountState> {
+        let calls = vec![
+            RpcCall::new("eth_getBalance", json!([address, "latest"])),
+            RpcCall::new("eth_getTransactionCount", json!([address, "latest"])),
+            RpcCall::new("eth_getCode", json!([address, "latest"])),
+        ];
+
+        let [balance, nonce, code]: [Result<Value>; 3] =
+            self.batch_request(calls).await.try_into().map_err(|_| {
+                ClientError::Decode("Unexpected number of batch responses".to_string())
+            })?;
+
+        Ok(AccountState {
+            balance: serde_json::from_value(balance?)?,
+            nonce: serde_json::from_value::<U64>(nonce?)?.to(),
+            code: serde_json::from_value(code?)?,
+        })
+    }
+
+    /// Fetches the native balances of many addresses using JSON-RPC batches.
+    ///
+    /// Returns one result per address, in the same order as `addresses`.
+    pub async fn get_native_balances(&self, addresses: &[Address]) -> Vec<Result<U256>> {
+        let calls = addresses
+            .iter()
+            .map(|address| RpcCall::new("eth_getBalance", json!([address, "latest"])))
+            .collect();
+
+        self.batch_request(calls)
+            .await
+            .into_iter()
+            .map(|result| Ok(serde_json::from_value(result?)?))
+            .collect()
+    }
+
+    /// Sends `calls` as JSON-RPC batches of at most the chain's `max_batch_size` requests.
+    ///
+    /// Returns one result per call, in the same order as `calls`. If the endpoint rejects a batch,
+    /// or answers only part of it, the unanswered calls are retried as individual requests.
+    pub async fn batch_request(&self, calls: Vec<RpcCall>) -> Vec<Result<Value>> {
+        let batch_size = self
+            .metadata
+            .max_batch_size
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+            .max(1);
+
+        let futures = calls.chunks(batch_size).map(|chunk| self.send_batch(chunk));
         join_all(futures).await.into_iter().flatten().collect()
     }
 
-    /// Fetches transactions for the given address using Etherscan.
+    /// Sends a single JSON-RPC batch, falling back to individual requests where needed.
+    async fn send_batch(&self, calls: &[RpcCall]) -> Vec<Result<Value>> {
+        if calls.len() == 1 {
+            return vec![self.send_single(&calls[0]).await];
+        }
+
+        let mut batch = self.rpc_client.new_batch();
+        let waiters = match calls
+            .iter()
+            .map(|call| batch.add_call::<_, Value>(call.method.clone(), &call.params))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(waiters) => waiters,
+            Err(err) => {
+                warn!("Failed to build batch for {}: {err}", self.metadata.name);
+                return join_all(calls.iter().map(|call| self.send_single(call))).await;
+            }
+        };
+
+        if let Err(err) = batch.send().await {
+            warn!(
+                "Batch of {} requests rejected on {}, falling back to single requests: {err}",
+                calls.len(),
+                self.metadata.name
+            );
+            return join_all(calls.iter().map(|call| self.send_single(call))).await;
+        }
+
+        let responses = join_all(waiters).await;
+        let futures = calls
+            .iter()
+            .zip(responses)
+            .map(|(call, response)| async move {
+                match response {
+                    Err(RpcError::Transport(TransportErrorKind::MissingBatchResponse(_))) => {
+                        self.send_single(call).await
+                    }
+                    response => Ok(response?),
+                }
+            });
+        join_all(futures).await
+    }
+
+    /// Sends a single JSON-RPC request outside of a batch.
+    async fn send_single(&self, call: &RpcCall) -> Result<Value> {
+        Ok(self
+            .rpc_client
+            .request::<_, Value>(call.method.clone(), call.params.clone())
+            .await?)
+    }
+
+    /// Performs an `eth_call` against `to` with the given calldata at the given block.
+    async fn eth_call<T: RpcRecv>(&self, to: Address, data: Vec<u8>, block: BlockId) -> Result<T> {
+        let call = json!({
+            "to": format!("{to:?}"),
+            "data": format!("0x{}", hex::encode(data)),
+        });
+
+        Ok(self
+            .rpc_client
+            .request::<_, T>("eth_call", (call, block))
+            .await?)
+    }
+
+    /// Fetches transactions for the given address from the chain's [`TransactionSource`].
     ///
     /// Requests exactly `offset` transactions for the specified `page`.
     /// If the returned list is full, performs a lightweight probe of the
@@ -155,6 +497,43 @@ impl EvmChainClient {
         page: u64,
         offset: u64,
     ) -> Result<(Vec<NormalTransaction>, bool)> {
+        self.explorer_page(page, offset, |params| {
+            self.transaction_source.transactions(address, params)
+        })
+        .await
+    }
+
+    /// Fetches internal transactions (contract-originated value transfers) for the given address
+    /// from the chain's transaction source (the explorer's `txlistinternal` action).
+    ///
+    /// Pagination works like [`Self::get_transactions`]: returns `(transactions, has_more)`.
+    pub async fn get_internal_transactions(
+        &self,
+        address: Address,
+        page: u64,
+        offset: u64,
+    ) -> Result<(Vec<InternalTransaction>, bool)> {
+        self.explorer_page(page, offset, |params| {
+            self.transaction_source
+                .internal_transactions(address, params)
+        })
+        .await
+    }
+
+    /// Fetches one page of an explorer list endpoint, newest first.
+    ///
+    /// Requests exactly `offset` results for `page` and, if the page is full, probes the next
+    /// page with a single-result request to determine whether more results exist.
+    async fn explorer_page<T, F, Fut>(
+        &self,
+        page: u64,
+        offset: u64,
+        fetch: F,
+    ) -> Result<(Vec<T>, bool)>
+    where
+        F: Fn(TxListParams) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>>,
+    {
         let base = TxListParams {
             start_block: 0,
             end_block: u64::MAX,
@@ -163,10 +542,7 @@ impl EvmChainClient {
             offset,
         };
 
-        let fetched = self
-            .etherscan
-            .get_transactions(&address, Some(base))
-            .await?;
+        let fetched = empty_if_no_transactions(fetch(base).await)?;
 
         let has_more = if fetched.len() as u64 == offset {
             // Probe the next page cheaply
@@ -175,10 +551,7 @@ impl EvmChainClient {
                 offset: 1,
                 ..base
             };
-            let next = self
-                .etherscan
-                .get_transactions(&address, Some(probe))
-                .await?;
+            let next = empty_if_no_transactions(fetch(probe).await)?;
             !next.is_empty()
         } else {
             false
@@ -188,6 +561,14 @@ impl EvmChainClient {
     }
 }
 
+/// Treats the explorer's "No transactions found" response as an empty page.
+fn empty_if_no_transactions<T>(result: Result<Vec<T>>) -> Result<Vec<T>> {
+    match result {
+        Err(ClientError::NoTransactions) => Ok(vec![]),
+        result => result,
+    }
+}
+
 /// Wrapper around a map of multiple `EvmChainClient`s, keyed by chain name.
 #[derive(Clone)]
 pub struct EvmClientRegistry(HashMap<String, EvmChainClient>);
@@ -202,15 +583,28 @@ impl EvmClientRegistry {
     ///
     /// Returns a map from chain name to balance (U256).
     pub async fn get_native_balances(&self, address: Address) -> Result<HashMap<String, U256>> {
+        self.get_native_balances_at(address, BlockId::latest())
+            .await
+    }
+
+    /// Fetches native balances for the given address across all chains at the given block.
+    ///
+    /// Block numbers and hashes are chain-specific, so this is mostly useful with block tags
+    /// such as `safe` or `finalized`.
+    pub async fn get_native_balances_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<HashMap<String, U256>> {
         let futures = self.0.iter().map(|(chain, client)| {
             let chain = chain.clone();
             async move {
                 info!("Fetching native balance for {chain}");
-                match client.get_native_balance(address).await {
-                    Ok(balance) => Ok::<(String, U256), Error>((chain, balance)),
+                match client.get_native_balance_at(address, block).await {
+                    Ok(balance) => Ok::<(String, U256), ClientError>((chain, balance)),
                     Err(e) => {
                         warn!("Failed to fetch native balance for {chain}: {e}");
-                        Ok::<(String, U256), Error>((chain, U256::ZERO))
+                        Ok::<(String, U256), ClientError>((chain, U256::ZERO))
                     }
                 }
             }
@@ -224,12 +618,23 @@ impl EvmClientRegistry {
     ///
     /// Returns a map from chain name to list of `TokenBalance`.
     pub async fn get_token_balances(&self, address: Address) -> HashMap<String, Vec<TokenBalance>> {
+        self.get_token_balances_at(address, BlockId::latest()).await
+    }
+
+    /// Fetches token balances for the given address across all chains at the given block.
+    ///
+    /// See [`Self::get_native_balances_at`] for caveats about block identifiers.
+    pub async fn get_token_balances_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> HashMap<String, Vec<TokenBalance>> {
         let futures = self.0.iter().filter_map(|(chain, client)| {
             let chain = chain.clone();
             if !client.tokens().is_empty() {
                 Some(async move {
                     info!("Fetching token balances for {chain}");
-                    (chain, client.get_token_balances(address).await)
+                    (chain, client.get_token_balances_at(address, block).await)
                 })
             } else {
                 None
@@ -255,10 +660,12 @@ impl EvmClientRegistry {
             async move {
                 info!("Fetching transactions for {chain}");
                 match client.get_transactions(address, page, offset).await {
-                    Ok((txs, _)) => Ok::<(String, Vec<NormalTransaction>), Error>((chain, txs)),
+                    Ok((txs, _)) => {
+                        Ok::<(String, Vec<NormalTransaction>), ClientError>((chain, txs))
+                    }
                     Err(e) => {
                         warn!("Failed to fetch transactions for {chain}: {e}");
-                        Ok::<(String, Vec<NormalTransaction>), Error>((chain, vec![]))
+                        Ok::<(String, Vec<NormalTransaction>), ClientError>((chain, vec![]))
                     }
                 }
             }
@@ -278,3 +685,295 @@ impl EvmClientRegistry {
         self.0.get(chain)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bytes, U256, address},
+        sol_types::SolCall,
+    };
+    use serde_json::Value;
+
+    use super::{EvmClientRegistry, balanceOfCall};
+    use crate::{
+        ClientError, MULTICALL3_ADDRESS, ScriptedBackend, ScriptedBatch, ScriptedResponse,
+        TokenBalanceResult, TokenMetadata,
+        multicall::{Call3Result, aggregate3Call},
+        test_utils::{chain_metadata, eth_call_params, scripted_client, token, word},
+    };
+
+    const OWNER: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const DAI: Address = address!("0x6B175474E89094C44Da98b954EedeAC495271d0F");
+    /// A token whose `balanceOf` reverts
+    const BROKEN: Address = address!("0x000000000000000000000000000000000000dEaD");
+
+    /// Answers `balanceOf` on USDC with 5 and on DAI with 0, reverting on any other contract.
+    fn balance_of(to: Address, data: &[u8]) -> Result<Bytes, ()> {
+        balanceOfCall::abi_decode(data).expect("not a balanceOf call");
+        match to {
+            USDC => Ok(word(5)),
+            DAI => Ok(word(0)),
+            _ => Err(()),
+        }
+    }
+
+    /// Scripts `eth_call` with [`balance_of`], aggregating calls sent to Multicall3.
+    fn scripted_balances() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_call", |params: &Value| {
+            let (to, data) = eth_call_params(params);
+            if to == MULTICALL3_ADDRESS {
+                let calls = aggregate3Call::abi_decode(&data).unwrap().calls;
+                let results: Vec<Call3Result> = calls
+                    .iter()
+                    .map(|call| match balance_of(call.target, &call.callData) {
+                        Ok(data) => Call3Result {
+                            success: true,
+                            returnData: data,
+                        },
+                        Err(()) => Call3Result {
+                            success: false,
+                            returnData: Bytes::new(),
+                        },
+                    })
+                    .collect();
+                return ScriptedResponse::result(Bytes::from(aggregate3Call::abi_encode_returns(
+                    &results,
+                )));
+            }
+            match balance_of(to, &data) {
+                Ok(data) => ScriptedResponse::result(data),
+                Err(()) => ScriptedResponse::error(3, "execution reverted"),
+            }
+        });
+        backend
+    }
+
+    fn tokens() -> Vec<TokenMetadata> {
+        vec![
+            token("USDC", USDC),
+            token("DAI", DAI),
+            token("BROKEN", BROKEN),
+        ]
+    }
+
+    fn assert_balances(results: Vec<TokenBalanceResult>) {
+        let symbols: Vec<_> = results.iter().map(|r| r.token.symbol.as_str()).collect();
+        assert_eq!(symbols, ["USDC", "DAI", "BROKEN"]);
+        assert_eq!(results[0].balance.as_ref().unwrap(), &U256::from(5));
+        assert_eq!(results[1].balance.as_ref().unwrap(), &U256::ZERO);
+        assert!(matches!(results[2].balance, Err(ClientError::Reverted(_))));
+    }
+
+    #[tokio::test]
+    async fn token_balances_with_single_calls() {
+        let backend = scripted_balances();
+        let client = scripted_client(chain_metadata("test", 1), &backend, tokens());
+
+        assert_balances(client.get_token_balance_results(OWNER).await);
+        assert_eq!(backend.requests().len(), 3);
+
+        let balances = client.get_token_balances(OWNER).await;
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].token.address, USDC);
+        assert_eq!(balances[0].balance, U256::from(5));
+    }
+
+    #[tokio::test]
+    async fn token_balances_through_multicall() {
+        let backend = scripted_balances();
+        let mut metadata = chain_metadata("test", 1);
+        metadata.multicall = Some(MULTICALL3_ADDRESS);
+        let client = scripted_client(metadata, &backend, tokens());
+
+        assert_balances(client.get_token_balance_results(OWNER).await);
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn token_balances_fall_back_to_single_calls_when_multicall_fails() {
+        let backend = scripted_balances();
+        // Multicall3 is not deployed, so the call returns no data
+        backend.once("eth_call", ScriptedResponse::result("0x"));
+        let mut metadata = chain_metadata("test", 1);
+        metadata.multicall = Some(MULTICALL3_ADDRESS);
+        let client = scripted_client(metadata, &backend, tokens());
+
+        assert_balances(client.get_token_balance_results(OWNER).await);
+        assert_eq!(backend.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn registry_reports_zero_for_failing_chains() {
+        let up = ScriptedBackend::new();
+        up.on("eth_getBalance", ScriptedResponse::result("0x10"));
+        let down = ScriptedBackend::new();
+        down.on(
+            "eth_getBalance",
+            ScriptedResponse::TransportError("connection refused".to_string()),
+        );
+        let registry = EvmClientRegistry::new(
+            [
+                ("up", scripted_client(chain_metadata("up", 1), &up, vec![])),
+                (
+                    "down",
+                    scripted_client(chain_metadata("down", 2), &down, vec![]),
+                ),
+            ]
+            .into_iter()
+            .map(|(name, client)| (name.to_string(), client))
+            .collect(),
+        );
+
+        let balances = registry.get_native_balances(OWNER).await.unwrap();
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["up"], U256::from(16));
+        assert_eq!(balances["down"], U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn registry_token_balances_skip_chains_without_tokens() {
+        let with_tokens = scripted_balances();
+        let without_tokens = scripted_balances();
+        let registry = EvmClientRegistry::new(
+            [
+                (
+                    "tokens",
+                    scripted_client(chain_metadata("tokens", 1), &with_tokens, tokens()),
+                ),
+                (
+                    "empty",
+                    scripted_client(chain_metadata("empty", 2), &without_tokens, vec![]),
+                ),
+            ]
+            .into_iter()
+            .map(|(name, client)| (name.to_string(), client))
+            .collect(),
+        );
+
+        let balances = registry.get_token_balances(OWNER).await;
+        assert_eq!(balances.keys().collect::<Vec<_>>(), ["tokens"]);
+        assert_eq!(balances["tokens"].len(), 1);
+        assert_eq!(balances["tokens"][0].token.address, USDC);
+        assert!(without_tokens.requests().is_empty());
+    }
+
+    /// Scripts `eth_getBalance` to return the last byte of the address as its balance, failing
+    /// for [`BROKEN`].
+    fn scripted_native_balances() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getBalance", |params: &Value| {
+            let address: Address = serde_json::from_value(params[0].clone()).unwrap();
+            if address == BROKEN {
+                return ScriptedResponse::error(-32000, "missing trie node");
+            }
+            ScriptedResponse::result(U256::from(address[19]))
+        });
+        backend
+    }
+
+    fn addresses(count: u8) -> Vec<Address> {
+        (1..=count).map(Address::with_last_byte).collect()
+    }
+
+    fn assert_native_balances(results: Vec<Result<U256, ClientError>>, count: u8) {
+        let balances: Vec<U256> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(balances, (1..=count).map(U256::from).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn native_balances_in_batches() {
+        let backend = scripted_native_balances();
+        let mut metadata = chain_metadata("test", 1);
+        metadata.max_batch_size = Some(2);
+        let client = scripted_client(metadata, &backend, vec![]);
+
+        let mut addresses = addresses(4);
+        addresses.push(BROKEN);
+        let mut results = client.get_native_balances(&addresses).await;
+
+        assert!(matches!(results.pop(), Some(Err(ClientError::Rpc { .. }))));
+        assert_native_balances(results, 4);
+        // the last chunk holds a single address, sent outside of a batch
+        assert_eq!(backend.batches(), [2, 2]);
+        assert_eq!(backend.requests().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn rejected_batches_fall_back_to_single_requests() {
+        let backend = scripted_native_balances();
+        backend.on_batch(ScriptedBatch::Reject(
+            "batch requests are not supported".to_string(),
+        ));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        assert_native_balances(client.get_native_balances(&addresses(3)).await, 3);
+        assert_eq!(backend.batches(), [3]);
+        assert_eq!(backend.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unanswered_batch_requests_are_sent_alone() {
+        let backend = scripted_native_balances();
+        backend.on_batch(ScriptedBatch::Truncate(1));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        assert_native_balances(client.get_native_balances(&addresses(3)).await, 3);
+        assert_eq!(backend.batches(), [3]);
+        // one answered in the batch, then the other two on their own
+        assert_eq!(backend.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn batch_responses_are_matched_by_id() {
+        let backend = scripted_native_balances();
+        backend.on_batch(ScriptedBatch::Reverse);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        assert_native_balances(client.get_native_balances(&addresses(3)).await, 3);
+        assert_eq!(backend.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn account_state_in_one_batch() {
+        let backend = ScriptedBackend::new();
+        backend
+            .on("eth_getBalance", ScriptedResponse::result("0x10"))
+            .on("eth_getTransactionCount", ScriptedResponse::result("0x2"))
+            .on("eth_getCode", ScriptedResponse::result("0x6080"));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let state = client.get_account_state(OWNER).await.unwrap();
+        assert_eq!(state.balance, U256::from(16));
+        assert_eq!(state.nonce, 2);
+        assert_eq!(state.code, Bytes::from_static(&[0x60, 0x80]));
+        assert_eq!(backend.batches(), [3]);
+
+        let methods: Vec<_> = backend
+            .requests()
+            .into_iter()
+            .map(|request| request.method)
+            .collect();
+        assert_eq!(
+            methods,
+            ["eth_getBalance", "eth_getTransactionCount", "eth_getCode"]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_state_fails_with_its_first_failed_request() {
+        let backend = ScriptedBackend::new();
+        backend
+            .on("eth_getBalance", ScriptedResponse::result("0x10"))
+            .on(
+                "eth_getTransactionCount",
+                ScriptedResponse::error(-32000, "header not found"),
+            )
+            .on("eth_getCode", ScriptedResponse::result("0x"));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client.get_account_state(OWNER).await.unwrap_err();
+        assert!(matches!(err, ClientError::Rpc { .. }));
+    }
+}
@@ -0,0 +1,263 @@
+//! Address classification for [`EvmChainClient`].
+
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, Bytes},
+    sol,
+    sol_types::SolCall,
+};
+use futures::try_join;
+
+use crate::{
+    EvmChainClient,
+    error::{ClientError, Result},
+    metadata::{AddressKind, SafeInfo},
+};
+
+/// Code of an EIP-7702 delegated EOA: this prefix followed by the delegate's address.
+const EIP7702_DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// Storage slot in which Safe proxies keep their singleton.
+const SAFE_SINGLETON_SLOT: B256 = B256::ZERO;
+
+sol! {
+    function getOwners() external view returns (address[]);
+    function getThreshold() external view returns (uint256);
+    function VERSION() external view returns (string);
+}
+
+impl EvmChainClient {
+    /// Detects what kind of account `address` is from its code and storage.
+    ///
+    /// Checks, in order, for an EOA, an EIP-7702 delegated EOA, an EIP-1967, beacon or EIP-1822
+    /// proxy, and a Safe wallet, and otherwise reports a plain contract.
+    pub async fn classify_address(&self, address: Address) -> Result<AddressKind> {
+        self.classify_address_at(address, BlockId::latest()).await
+    }
+
+    /// Detects what kind of account `address` was at the given block, like
+    /// [`Self::classify_address`].
+    pub async fn classify_address_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<AddressKind> {
+        let code = self
+            .rpc_client
+            .request::<_, Bytes>("eth_getCode", (address, block))
+            .await?;
+        if code.is_empty() {
+            return Ok(AddressKind::Eoa);
+        }
+        if let Some(delegate) = code.strip_prefix(&EIP7702_DELEGATION_PREFIX)
+            && delegate.len() == Address::len_bytes()
+        {
+            return Ok(AddressKind::Delegated {
+                delegate: Address::from_slice(delegate),
+            });
+        }
+
+        if let Some(proxy) = self.get_proxy_at(address, block).await? {
+            return Ok(AddressKind::Proxy(proxy));
+        }
+        if let Some(safe) = self.get_safe(address, block).await? {
+            return Ok(AddressKind::Safe(safe));
+        }
+        Ok(AddressKind::Contract)
+    }
+
+    /// Reads the configuration of the Safe wallet at `address` at the given block, or `None` if
+    /// it is not a Safe.
+    async fn get_safe(&self, address: Address, block: BlockId) -> Result<Option<SafeInfo>> {
+        let Some(singleton) = self
+            .get_storage_address(address, SAFE_SINGLETON_SLOT, block)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // other contracts can hold an address in slot 0 too, and revert these calls
+        let (threshold, owners) = match try_join!(
+            self.eth_call::<Bytes>(address, getThresholdCall {}.abi_encode(), block),
+            self.eth_call::<Bytes>(address, getOwnersCall {}.abi_encode(), block),
+        ) {
+            Ok((threshold, owners)) => (threshold, owners),
+            Err(ClientError::Reverted(_) | ClientError::Rpc { .. }) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let (Ok(threshold), Ok(owners)) = (
+            getThresholdCall::abi_decode_returns(&threshold),
+            getOwnersCall::abi_decode_returns(&owners),
+        ) else {
+            return Ok(None);
+        };
+        let Ok(threshold) = u64::try_from(threshold) else {
+            return Ok(None);
+        };
+        if threshold == 0 || threshold > owners.len() as u64 {
+            return Ok(None);
+        }
+
+        let version = self
+            .eth_call::<Bytes>(address, VERSIONCall {}.abi_encode(), block)
+            .await
+            .ok()
+            .and_then(|data| VERSIONCall::abi_decode_returns(&data).ok());
+
+        Ok(Some(SafeInfo {
+            singleton,
+            version,
+            owners,
+            threshold,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        eips::BlockId,
+        primitives::{Address, B256, Bytes, address},
+        sol_types::SolCall,
+    };
+    use serde_json::{Value, json};
+
+    use super::{
+        EIP7702_DELEGATION_PREFIX, SAFE_SINGLETON_SLOT, VERSIONCall, getOwnersCall,
+        getThresholdCall,
+    };
+    use crate::{
+        ScriptedBackend, ScriptedResponse,
+        client::contracts::{EIP1967_BEACON_SLOT, EIP1967_IMPLEMENTATION_SLOT},
+        metadata::{AddressKind, ProxyInfo, ProxyStandard, SafeInfo},
+        test_utils::{chain_metadata, eth_call_params, scripted_client, word},
+    };
+
+    const ACCOUNT: Address = address!("0x00000000000000000000000000000000000000aa");
+    const TARGET: Address = address!("0x00000000000000000000000000000000000000bb");
+    const OWNERS: [Address; 2] = [
+        address!("0x00000000000000000000000000000000000000c1"),
+        address!("0x00000000000000000000000000000000000000c2"),
+    ];
+    const BLOCK: u64 = 16;
+
+    /// Scripts an account with `code`, holding [`TARGET`] in `slot` if set, and answering the
+    /// Safe getters if `safe` is set.
+    fn scripted_account(code: Bytes, slot: Option<B256>, safe: bool) -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on("eth_getCode", ScriptedResponse::result(code));
+        backend.on_request("eth_getStorageAt", move |params: &Value| {
+            let requested: B256 = serde_json::from_value(params[1].clone()).unwrap();
+            if Some(requested) == slot {
+                ScriptedResponse::result(TARGET.into_word())
+            } else {
+                ScriptedResponse::result(B256::ZERO)
+            }
+        });
+        backend.on_request("eth_call", move |params: &Value| {
+            let (_, data) = eth_call_params(params);
+            if !safe {
+                return ScriptedResponse::error(3, "execution reverted");
+            }
+            let data = match data[..4].try_into().unwrap() {
+                getThresholdCall::SELECTOR => word(2),
+                getOwnersCall::SELECTOR => {
+                    getOwnersCall::abi_encode_returns(&OWNERS.to_vec()).into()
+                }
+                VERSIONCall::SELECTOR => {
+                    VERSIONCall::abi_encode_returns(&"1.3.0".to_string()).into()
+                }
+                _ => return ScriptedResponse::error(3, "execution reverted"),
+            };
+            ScriptedResponse::result(data)
+        });
+        backend
+    }
+
+    /// Classifies [`ACCOUNT`] at [`BLOCK`], checking that every request reads that block.
+    async fn classify(backend: &ScriptedBackend) -> AddressKind {
+        let client = scripted_client(chain_metadata("test", 1), backend, vec![]);
+        let kind = client
+            .classify_address_at(ACCOUNT, BlockId::number(BLOCK))
+            .await
+            .unwrap();
+
+        for request in backend.requests() {
+            let params = request.params.as_array().unwrap();
+            assert_eq!(params.last().unwrap(), &json!(format!("{BLOCK:#x}")));
+        }
+        kind
+    }
+
+    #[tokio::test]
+    async fn classifies_eoas() {
+        let backend = scripted_account(Bytes::new(), None, false);
+        assert_eq!(classify(&backend).await, AddressKind::Eoa);
+    }
+
+    #[tokio::test]
+    async fn classifies_delegated_eoas() {
+        let code = [&EIP7702_DELEGATION_PREFIX[..], TARGET.as_slice()].concat();
+        let backend = scripted_account(code.into(), None, false);
+        assert_eq!(
+            classify(&backend).await,
+            AddressKind::Delegated { delegate: TARGET }
+        );
+    }
+
+    #[tokio::test]
+    async fn classifies_eip1967_proxies() {
+        let backend = scripted_account(
+            Bytes::from_static(&[0x60, 0x80]),
+            Some(EIP1967_IMPLEMENTATION_SLOT),
+            false,
+        );
+        assert_eq!(
+            classify(&backend).await,
+            AddressKind::Proxy(ProxyInfo {
+                standard: ProxyStandard::Eip1967,
+                implementation: TARGET,
+                beacon: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn classifies_safes() {
+        let backend = scripted_account(
+            Bytes::from_static(&[0x60, 0x80]),
+            Some(SAFE_SINGLETON_SLOT),
+            true,
+        );
+        assert_eq!(
+            classify(&backend).await,
+            AddressKind::Safe(SafeInfo {
+                singleton: TARGET,
+                version: Some("1.3.0".to_string()),
+                owners: OWNERS.to_vec(),
+                threshold: 2,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn contracts_with_an_address_in_slot_zero_are_not_safes() {
+        let backend = scripted_account(
+            Bytes::from_static(&[0x60, 0x80]),
+            Some(SAFE_SINGLETON_SLOT),
+            false,
+        );
+        assert_eq!(classify(&backend).await, AddressKind::Contract);
+    }
+
+    #[tokio::test]
+    async fn contracts_with_an_address_in_the_beacon_slot_are_not_proxies() {
+        // `implementation()` reverts on the address in the slot
+        let backend = scripted_account(
+            Bytes::from_static(&[0x60, 0x80]),
+            Some(EIP1967_BEACON_SLOT),
+            false,
+        );
+        assert_eq!(classify(&backend).await, AddressKind::Contract);
+    }
+}
@@ -0,0 +1,239 @@
+//! Block lookups and timestamp-to-block resolution for [`EvmChainClient`].
+
+use std::{num::NonZeroUsize, sync::MutexGuard};
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{U64, U128},
+};
+use lru::LruCache;
+use serde::Deserialize;
+
+use crate::{
+    EvmChainClient,
+    error::{ClientError, Result},
+};
+
+/// The subset of an `eth_getBlockByNumber` response needed to resolve timestamps and fees.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub(super) struct BlockHeader {
+    pub(super) number: U64,
+    pub(super) timestamp: U64,
+    #[serde(rename = "baseFeePerGas", default)]
+    pub(super) base_fee_per_gas: Option<U128>,
+}
+
+/// Maximum number of block timestamps cached per chain.
+///
+/// A timestamp lookup reads about `log2(latest block)` headers, so this keeps the headers of
+/// roughly a hundred recent lookups.
+const MAX_CACHED_TIMESTAMPS: NonZeroUsize = NonZeroUsize::new(4096).unwrap();
+
+/// Maximum number of resolved timestamp lookups cached per chain.
+const MAX_CACHED_LOOKUPS: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+/// Caches block timestamps and resolved timestamp lookups for a chain, evicting the least
+/// recently used entries.
+#[derive(Debug)]
+pub(crate) struct BlockTimeCache {
+    /// Block number to block timestamp
+    timestamps: LruCache<u64, u64>,
+    /// UNIX timestamp to the last block at or before it
+    blocks: LruCache<u64, u64>,
+}
+
+impl Default for BlockTimeCache {
+    fn default() -> Self {
+        Self {
+            timestamps: LruCache::new(MAX_CACHED_TIMESTAMPS),
+            blocks: LruCache::new(MAX_CACHED_LOOKUPS),
+        }
+    }
+}
+
+impl EvmChainClient {
+    /// Returns the number of the latest block.
+    pub async fn get_block_number(&self) -> Result<u64> {
+        Ok(self
+            .rpc_client
+            .request_noparams::<U64>("eth_blockNumber")
+            .await?
+            .to())
+    }
+
+    /// Returns the number of the last block mined at or before the given UNIX `timestamp`.
+    ///
+    /// Binary searches over `eth_getBlockByNumber`. Block timestamps and resolved lookups are
+    /// cached, so repeated lookups around the same time only cost a few requests.
+    pub async fn get_block_at_timestamp(&self, timestamp: u64) -> Result<u64> {
+        if let Some(block) = self.block_times().blocks.get(&timestamp) {
+            return Ok(*block);
+        }
+
+        let latest = self.get_block_header(BlockNumberOrTag::Latest).await?;
+        let latest_number = latest.number.to::<u64>();
+        if timestamp >= latest.timestamp.to::<u64>() {
+            // not cached, a later block may still be mined before `timestamp`
+            return Ok(latest_number);
+        }
+
+        let genesis_timestamp = self.get_block_timestamp(0).await?;
+        if timestamp < genesis_timestamp {
+            return Err(ClientError::NotFound(format!(
+                "No block on {} at or before timestamp {timestamp}",
+                self.metadata.name
+            )));
+        }
+
+        // invariant: timestamp(low) <= timestamp < timestamp(high)
+        let (mut low, mut high) = (0, latest_number);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.get_block_timestamp(mid).await? <= timestamp {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        self.block_times().blocks.put(timestamp, low);
+        Ok(low)
+    }
+
+    /// Returns the timestamp of the given block number, using the cache when possible.
+    async fn get_block_timestamp(&self, number: u64) -> Result<u64> {
+        if let Some(timestamp) = self.block_times().timestamps.get(&number) {
+            return Ok(*timestamp);
+        }
+
+        let header = self.get_block_header(number.into()).await?;
+        Ok(header.timestamp.to())
+    }
+
+    /// Fetches a block header, caching its timestamp.
+    pub(super) async fn get_block_header(&self, block: BlockNumberOrTag) -> Result<BlockHeader> {
+        let header = self
+            .rpc_client
+            .request::<_, Option<BlockHeader>>("eth_getBlockByNumber", (block, false))
+            .await?
+            .ok_or_else(|| {
+                ClientError::NotFound(format!("Block {block} not found on {}", self.metadata.name))
+            })?;
+
+        self.block_times()
+            .timestamps
+            .put(header.number.to(), header.timestamp.to());
+        Ok(header)
+    }
+
+    fn block_times(&self) -> MutexGuard<'_, BlockTimeCache> {
+        self.block_times
+            .lock()
+            .expect("block time cache lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{eips::BlockNumberOrTag, primitives::U64};
+    use serde_json::{Value, json};
+
+    use crate::{
+        ClientError, ScriptedBackend, ScriptedResponse,
+        test_utils::{chain_metadata, scripted_client},
+    };
+
+    const GENESIS_TIMESTAMP: u64 = 1_000;
+    const BLOCK_TIME: u64 = 12;
+    const LATEST: u64 = 100;
+
+    fn timestamp(number: u64) -> u64 {
+        GENESIS_TIMESTAMP + number * BLOCK_TIME
+    }
+
+    /// Scripts a chain of [`LATEST`] blocks mined every [`BLOCK_TIME`] seconds.
+    fn scripted_chain() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getBlockByNumber", |params: &Value| {
+            let block: BlockNumberOrTag = serde_json::from_value(params[0].clone()).unwrap();
+            let number = block.as_number().unwrap_or(LATEST);
+            ScriptedResponse::result(json!({
+                "number": U64::from(number),
+                "timestamp": U64::from(timestamp(number)),
+            }))
+        });
+        backend
+    }
+
+    #[tokio::test]
+    async fn resolves_exact_and_intermediate_timestamps() {
+        let backend = scripted_chain();
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        assert_eq!(
+            client.get_block_at_timestamp(timestamp(37)).await.unwrap(),
+            37
+        );
+        assert_eq!(
+            client
+                .get_block_at_timestamp(timestamp(37) + BLOCK_TIME - 1)
+                .await
+                .unwrap(),
+            37
+        );
+        assert_eq!(
+            client
+                .get_block_at_timestamp(GENESIS_TIMESTAMP)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn timestamps_after_the_latest_block_resolve_to_it() {
+        let backend = scripted_chain();
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let after = timestamp(LATEST) + 60;
+        assert_eq!(client.get_block_at_timestamp(after).await.unwrap(), LATEST);
+        assert_eq!(backend.requests().len(), 1);
+
+        // not cached, as the next block may still be mined before it
+        assert_eq!(client.get_block_at_timestamp(after).await.unwrap(), LATEST);
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn timestamps_before_genesis_are_not_found() {
+        let backend = scripted_chain();
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client
+            .get_block_at_timestamp(GENESIS_TIMESTAMP - 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn resolved_lookups_are_cached() {
+        let backend = scripted_chain();
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let between = timestamp(62) + 5;
+        assert_eq!(client.get_block_at_timestamp(between).await.unwrap(), 62);
+        let requests = backend.requests().len();
+        assert!(requests > 2);
+
+        assert_eq!(client.get_block_at_timestamp(between).await.unwrap(), 62);
+        assert_eq!(backend.requests().len(), requests);
+
+        // block timestamps read by the first search are reused by the next
+        assert_eq!(
+            client.get_block_at_timestamp(between + 1).await.unwrap(),
+            62
+        );
+        assert_eq!(backend.requests().len(), requests + 1);
+    }
+}
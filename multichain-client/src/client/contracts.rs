@@ -0,0 +1,392 @@
+//! Contract ABI lookup for [`EvmChainClient`], following upgradeable proxies.
+
+use alloy::{
+    eips::BlockId,
+    json_abi::JsonAbi,
+    primitives::{Address, B256, Bytes, b256},
+    sol,
+    sol_types::SolCall,
+};
+use tracing::warn;
+
+use crate::{
+    EvmChainClient,
+    abi::AbiCache,
+    error::{ClientError, Result},
+    metadata::{ProxyInfo, ProxyStandard},
+};
+
+/// EIP-1967 implementation slot, `keccak256("eip1967.proxy.implementation") - 1`.
+pub(super) const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// EIP-1967 beacon slot, `keccak256("eip1967.proxy.beacon") - 1`.
+pub(super) const EIP1967_BEACON_SLOT: B256 =
+    b256!("0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// EIP-1822 (UUPS) implementation slot, `keccak256("PROXIABLE")`.
+const EIP1822_PROXIABLE_SLOT: B256 =
+    b256!("0xc5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7");
+
+sol! {
+    function implementation() external view returns (address);
+}
+
+impl EvmChainClient {
+    /// Sets the on-disk cache for contract ABIs fetched by [`Self::get_contract_abi`].
+    pub fn with_abi_cache(mut self, cache: AbiCache) -> Self {
+        self.abi_cache = Some(cache);
+        self
+    }
+
+    /// Returns the implementation behind an EIP-1967 or EIP-1822 proxy at `address`, or `None`
+    /// if it is not a proxy.
+    ///
+    /// EIP-1967 beacon proxies are resolved by calling `implementation()` on the beacon.
+    pub async fn get_proxy_implementation(&self, address: Address) -> Result<Option<Address>> {
+        Ok(self
+            .get_proxy(address)
+            .await?
+            .map(|proxy| proxy.implementation))
+    }
+
+    /// Detects an EIP-1967, EIP-1967 beacon or EIP-1822 proxy at `address` from its storage.
+    ///
+    /// An address in the beacon slot only counts as a beacon if its `implementation()` returns
+    /// a non-zero address.
+    pub async fn get_proxy(&self, address: Address) -> Result<Option<ProxyInfo>> {
+        self.get_proxy_at(address, BlockId::latest()).await
+    }
+
+    /// Detects a proxy at `address` from its storage at the given block, like
+    /// [`Self::get_proxy`].
+    pub async fn get_proxy_at(
+        &self,
+        address: Address,
+        block: BlockId,
+    ) -> Result<Option<ProxyInfo>> {
+        for (standard, slot) in [
+            (ProxyStandard::Eip1967, EIP1967_IMPLEMENTATION_SLOT),
+            (ProxyStandard::Eip1822, EIP1822_PROXIABLE_SLOT),
+        ] {
+            if let Some(implementation) = self.get_storage_address(address, slot, block).await? {
+                return Ok(Some(ProxyInfo {
+                    standard,
+                    implementation,
+                    beacon: None,
+                }));
+            }
+        }
+
+        let Some(beacon) = self
+            .get_storage_address(address, EIP1967_BEACON_SLOT, block)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let implementation = match self
+            .eth_call::<Bytes>(beacon, implementationCall {}.abi_encode(), block)
+            .await
+        {
+            Ok(data) => implementationCall::abi_decode_returns(&data).ok(),
+            // other contracts may use the beacon slot for something else
+            Err(ClientError::Reverted(_) | ClientError::Rpc { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        Ok(implementation
+            .filter(|implementation| !implementation.is_zero())
+            .map(|implementation| ProxyInfo {
+                standard: ProxyStandard::Beacon,
+                implementation,
+                beacon: Some(beacon),
+            }))
+    }
+
+    /// Fetches the verified ABI of the contract at `address`.
+    ///
+    /// For proxies, the implementation's ABI is returned, extended with the functions and
+    /// events of the proxy itself when it is verified too. ABIs and the implementation behind
+    /// each contract are read from and written to the [`AbiCache`] when one is set, so each
+    /// contract is only fetched from the explorer once, and fully cached lookups make no
+    /// request at all. Fails with [`ClientError::NotFound`] if the contract is not verified.
+    pub async fn get_contract_abi(&self, address: Address) -> Result<JsonAbi> {
+        let implementation = self
+            .get_cached_implementation(address)
+            .await?
+            .filter(|implementation| *implementation != address);
+        let abi = self.get_verified_abi(address).await;
+        let Some(implementation) = implementation else {
+            return abi;
+        };
+
+        let implementation_abi = self.get_verified_abi(implementation).await?;
+        match abi {
+            Ok(proxy_abi) => Ok(merge_abis(implementation_abi, proxy_abi)),
+            Err(ClientError::NotFound(_)) => Ok(implementation_abi),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the implementation behind `address`, through the cache.
+    ///
+    /// If its storage cannot be read but the ABI of `address` is cached, the contract is
+    /// treated as not being a proxy rather than failing the lookup.
+    async fn get_cached_implementation(&self, address: Address) -> Result<Option<Address>> {
+        let chain_id = self.metadata.chain_id;
+        let Some(cache) = &self.abi_cache else {
+            return self.get_proxy_implementation(address).await;
+        };
+        if let Some(implementation) = cache.get_implementation(chain_id, address) {
+            return Ok(implementation);
+        }
+
+        match self.get_proxy_implementation(address).await {
+            Ok(implementation) => {
+                if let Err(err) = cache.insert_implementation(chain_id, address, implementation) {
+                    warn!("Failed to cache implementation of {address}: {err}");
+                }
+                Ok(implementation)
+            }
+            Err(err) if matches!(cache.get(chain_id, address), Some(Some(_))) => {
+                warn!("Using the cached ABI of {address}, failed to check for a proxy: {err}");
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetches the verified ABI of `address` itself, through the cache.
+    async fn get_verified_abi(&self, address: Address) -> Result<JsonAbi> {
+        let chain_id = self.metadata.chain_id;
+        if let Some(cached) = self
+            .abi_cache
+            .as_ref()
+            .and_then(|cache| cache.get(chain_id, address))
+        {
+            return cached.ok_or_else(|| {
+                ClientError::NotFound(format!("Verified source code of {address}"))
+            });
+        }
+
+        let result = self.transaction_source.contract_abi(address).await;
+        if let Some(cache) = &self.abi_cache {
+            let entry = match &result {
+                Ok(abi) => Some(Some(abi)),
+                Err(ClientError::NotFound(_)) => Some(None),
+                Err(_) => None,
+            };
+            if let Some(abi) = entry
+                && let Err(err) = cache.insert(chain_id, address, abi)
+            {
+                warn!("Failed to cache ABI of {address}: {err}");
+            }
+        }
+        result
+    }
+
+    /// Reads an address stored in `slot` of `contract` at the given block, or `None` if the slot
+    /// is empty or holds something other than an address.
+    pub(super) async fn get_storage_address(
+        &self,
+        contract: Address,
+        slot: B256,
+        block: BlockId,
+    ) -> Result<Option<Address>> {
+        let word = self
+            .rpc_client
+            .request::<_, B256>("eth_getStorageAt", (contract, slot, block))
+            .await?;
+        let address = Address::from_word(word);
+        let is_address = word[..12].iter().all(|byte| *byte == 0);
+        Ok((is_address && !address.is_zero()).then_some(address))
+    }
+}
+
+/// Adds the functions and events of `proxy` that `implementation` does not already define.
+fn merge_abis(mut implementation: JsonAbi, proxy: JsonAbi) -> JsonAbi {
+    for function in proxy.functions() {
+        let selector = function.selector();
+        if implementation.functions().all(|f| f.selector() != selector) {
+            implementation
+                .functions
+                .entry(function.name.clone())
+                .or_default()
+                .push(function.clone());
+        }
+    }
+    for event in proxy.events() {
+        let selector = event.selector();
+        if implementation.events().all(|e| e.selector() != selector) {
+            implementation
+                .events
+                .entry(event.name.clone())
+                .or_default()
+                .push(event.clone());
+        }
+    }
+    implementation
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use alloy::{
+        eips::BlockId,
+        json_abi::JsonAbi,
+        primitives::{Address, B256, Bytes, address},
+        sol_types::SolCall,
+    };
+    use serde_json::Value;
+
+    use super::{EIP1967_BEACON_SLOT, EIP1967_IMPLEMENTATION_SLOT, implementationCall};
+    use crate::{
+        AbiCache, ScriptedBackend, ScriptedResponse,
+        metadata::{ProxyInfo, ProxyStandard},
+        test_utils::{chain_metadata, eth_call_params, scripted_client},
+    };
+
+    const PROXY: Address = address!("0x00000000000000000000000000000000000000aa");
+    const IMPLEMENTATION: Address = address!("0x00000000000000000000000000000000000000bb");
+    const BEACON: Address = address!("0x00000000000000000000000000000000000000cc");
+
+    /// Returns an empty cache directory unique to `name`.
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("abis-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn abi(signature: &str) -> JsonAbi {
+        JsonAbi::parse([signature]).unwrap()
+    }
+
+    /// Scripts `eth_getStorageAt` for an EIP-1967 proxy at [`PROXY`].
+    fn scripted_proxy() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getStorageAt", |params: &Value| {
+            let contract: Address = serde_json::from_value(params[0].clone()).unwrap();
+            let slot: B256 = serde_json::from_value(params[1].clone()).unwrap();
+            if contract == PROXY && slot == EIP1967_IMPLEMENTATION_SLOT {
+                ScriptedResponse::result(IMPLEMENTATION.into_word())
+            } else {
+                ScriptedResponse::result(B256::ZERO)
+            }
+        });
+        backend
+    }
+
+    /// Scripts a contract at [`PROXY`] holding [`BEACON`] in the beacon slot, answering
+    /// `implementation()` with `implementation`, or reverting if it is `None`.
+    fn scripted_beacon(implementation: Option<Address>) -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getStorageAt", |params: &Value| {
+            let slot: B256 = serde_json::from_value(params[1].clone()).unwrap();
+            if slot == EIP1967_BEACON_SLOT {
+                ScriptedResponse::result(BEACON.into_word())
+            } else {
+                ScriptedResponse::result(B256::ZERO)
+            }
+        });
+        backend.on_request("eth_call", move |params: &Value| {
+            let (to, data) = eth_call_params(params);
+            assert_eq!(to, BEACON);
+            assert_eq!(data[..4], implementationCall::SELECTOR);
+            match implementation {
+                Some(implementation) => ScriptedResponse::result(Bytes::from(
+                    implementationCall::abi_encode_returns(&implementation),
+                )),
+                None => ScriptedResponse::error(3, "execution reverted"),
+            }
+        });
+        backend
+    }
+
+    #[tokio::test]
+    async fn detects_beacon_proxies() {
+        let backend = scripted_beacon(Some(IMPLEMENTATION));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let proxy = client
+            .get_proxy_at(PROXY, BlockId::number(16))
+            .await
+            .unwrap();
+        assert_eq!(
+            proxy,
+            Some(ProxyInfo {
+                standard: ProxyStandard::Beacon,
+                implementation: IMPLEMENTATION,
+                beacon: Some(BEACON),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn beacon_slots_without_a_beacon_are_not_proxies() {
+        let backend = scripted_beacon(None);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+        assert_eq!(client.get_proxy(PROXY).await.unwrap(), None);
+
+        let backend = scripted_beacon(Some(Address::ZERO));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+        assert_eq!(client.get_proxy(PROXY).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn proxy_abis_are_served_from_the_cache() {
+        let dir = cache_dir("proxy");
+        let cache = AbiCache::new(&dir);
+        cache
+            .insert(1, IMPLEMENTATION, Some(&abi("function upgraded()")))
+            .unwrap();
+        cache.insert(1, PROXY, None).unwrap();
+
+        let backend = scripted_proxy();
+        let client =
+            scripted_client(chain_metadata("test", 1), &backend, vec![]).with_abi_cache(cache);
+        let abi = client.get_contract_abi(PROXY).await.unwrap();
+        assert!(abi.function("upgraded").is_some());
+        assert_eq!(
+            AbiCache::new(&dir).get_implementation(1, PROXY),
+            Some(Some(IMPLEMENTATION))
+        );
+
+        // the implementation is cached too, so no request is needed anymore
+        let requests = backend.requests().len();
+        backend.on(
+            "eth_getStorageAt",
+            ScriptedResponse::TransportError("connection refused".to_string()),
+        );
+        let abi = client.get_contract_abi(PROXY).await.unwrap();
+        assert!(abi.function("upgraded").is_some());
+        assert_eq!(backend.requests().len(), requests);
+
+        let abis = AbiCache::new(&dir).abis();
+        let contracts: Vec<_> = abis.iter().map(|(_, address, _)| *address).collect();
+        assert_eq!(contracts, [IMPLEMENTATION, PROXY]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cached_abis_survive_rpc_failures() {
+        let dir = cache_dir("rpc-failure");
+        let cache = AbiCache::new(&dir);
+        cache
+            .insert(1, PROXY, Some(&abi("function owner() returns (address)")))
+            .unwrap();
+
+        let backend = ScriptedBackend::new();
+        backend.on(
+            "eth_getStorageAt",
+            ScriptedResponse::TransportError("connection refused".to_string()),
+        );
+        let client =
+            scripted_client(chain_metadata("test", 1), &backend, vec![]).with_abi_cache(cache);
+        let abi = client.get_contract_abi(PROXY).await.unwrap();
+        assert!(abi.function("owner").is_some());
+
+        // without a cached ABI, the failure is reported
+        assert!(client.get_contract_abi(IMPLEMENTATION).await.is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+//! ENS forward and reverse resolution for [`EvmChainClient`] and [`EvmClientRegistry`].
+
+use std::{
+    num::NonZeroUsize,
+    sync::MutexGuard,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, Bytes},
+    sol,
+    sol_types::SolCall,
+};
+use lru::LruCache;
+
+use crate::{
+    EvmChainClient, EvmClientRegistry,
+    ens::{MAINNET_ENS_REGISTRY, namehash, normalize_name, reverse_name},
+    error::{ClientError, Result},
+};
+
+/// How long a reverse lookup is cached.
+const ENS_NAME_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Maximum number of reverse lookups cached per chain.
+const MAX_CACHED_ENS_NAMES: NonZeroUsize = NonZeroUsize::new(4096).unwrap();
+
+sol! {
+    function resolver(bytes32 node) external view returns (address);
+    function addr(bytes32 node) external view returns (address);
+    function name(bytes32 node) external view returns (string);
+}
+
+/// Caches verified primary names, keyed by address, evicting the least recently used.
+#[derive(Debug)]
+pub(crate) struct EnsNameCache(LruCache<Address, (Option<String>, Instant)>);
+
+impl Default for EnsNameCache {
+    fn default() -> Self {
+        Self(LruCache::new(MAX_CACHED_ENS_NAMES))
+    }
+}
+
+impl EvmChainClient {
+    /// Returns the address of the chain's ENS registry, if ENS is available on it.
+    ///
+    /// This is the `ensRegistry` setting of the chain, or the mainnet registry for chain ID 1.
+    pub fn ens_registry(&self) -> Option<Address> {
+        self.metadata
+            .ens_registry
+            .or((self.metadata.chain_id == 1).then_some(MAINNET_ENS_REGISTRY))
+    }
+
+    /// Resolves an ENS name (e.g., "vitalik.eth") to an address.
+    ///
+    /// Fails with [`ClientError::InvalidName`] if the name cannot be normalized,
+    /// [`ClientError::NotFound`] if it has no resolver or address, and
+    /// [`ClientError::Unsupported`] if the chain has no ENS registry.
+    pub async fn resolve_name(&self, name: &str) -> Result<Address> {
+        let name = normalize_name(name)?;
+        let node = namehash(&name);
+        let not_found = || ClientError::NotFound(format!("ENS name {name}"));
+
+        let resolver = self.ens_resolver(node).await?.ok_or_else(not_found)?;
+        let data = self
+            .eth_call::<Bytes>(resolver, addrCall { node }.abi_encode(), BlockId::latest())
+            .await?;
+        let address = addrCall::abi_decode_returns(&data)?;
+        if address.is_zero() {
+            return Err(not_found());
+        }
+        Ok(address)
+    }
+
+    /// Looks up the primary ENS name of `address`.
+    ///
+    /// The reverse record is only trusted if the name resolves back to `address`, since anyone
+    /// can set a reverse record claiming any name. Primary names that [`normalize_name`] rejects,
+    /// such as names with non-ASCII characters, are reported as no name. Results are cached for
+    /// ten minutes.
+    pub async fn lookup_address(&self, address: Address) -> Result<Option<String>> {
+        // expired entries are overwritten below
+        if let Some((name, at)) = self.ens_names().0.get(&address)
+            && at.elapsed() < ENS_NAME_TTL
+        {
+            return Ok(name.clone());
+        }
+
+        let name = self.lookup_reverse_record(address).await?;
+        let verified = match name {
+            Some(name) => match self.resolve_name(&name).await {
+                Ok(resolved) if resolved == address => Some(name),
+                Ok(_) | Err(ClientError::NotFound(_) | ClientError::InvalidName(_)) => None,
+                Err(err) => return Err(err),
+            },
+            None => None,
+        };
+
+        self.ens_names()
+            .0
+            .put(address, (verified.clone(), Instant::now()));
+        Ok(verified)
+    }
+
+    /// Reads the unverified reverse record of `address`.
+    async fn lookup_reverse_record(&self, address: Address) -> Result<Option<String>> {
+        let node = namehash(&reverse_name(address));
+        let Some(resolver) = self.ens_resolver(node).await? else {
+            return Ok(None);
+        };
+        let data = self
+            .eth_call::<Bytes>(resolver, nameCall { node }.abi_encode(), BlockId::latest())
+            .await?;
+        let name = nameCall::abi_decode_returns(&data)?;
+        Ok((!name.is_empty()).then_some(name))
+    }
+
+    /// Returns the resolver of `node`, or `None` if it has none.
+    async fn ens_resolver(&self, node: B256) -> Result<Option<Address>> {
+        let registry = self.ens_registry().ok_or_else(|| {
+            ClientError::Unsupported(format!("No ENS registry on chain: {}", self.metadata.name))
+        })?;
+        let data = self
+            .eth_call::<Bytes>(
+                registry,
+                resolverCall { node }.abi_encode(),
+                BlockId::latest(),
+            )
+            .await?;
+        // the registry has no code if `ensRegistry` points at the wrong address
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let resolver = resolverCall::abi_decode_returns(&data)?;
+        Ok((!resolver.is_zero()).then_some(resolver))
+    }
+
+    fn ens_names(&self) -> MutexGuard<'_, EnsNameCache> {
+        self.ens_names.lock().expect("ENS name cache lock poisoned")
+    }
+}
+
+impl EvmClientRegistry {
+    /// Returns the client ENS names are resolved with for `chain`.
+    ///
+    /// ENS mostly lives on Ethereum mainnet, so chains without their own registry fall back to
+    /// the mainnet client, if it is loaded.
+    pub fn ens_client(&self, chain: &str) -> Option<&EvmChainClient> {
+        let has_registry = |client: &&EvmChainClient| client.ens_registry().is_some();
+        self.get(chain).filter(has_registry).or_else(|| {
+            self.inner()
+                .values()
+                .find(|client| client.metadata().chain_id == 1)
+                .filter(has_registry)
+        })
+    }
+
+    /// Resolves an ENS name for use on `chain`, see [`Self::ens_client`].
+    pub async fn resolve_name(&self, chain: &str, name: &str) -> Result<Address> {
+        self.ens_client(chain)
+            .ok_or_else(|| no_ens_client(chain))?
+            .resolve_name(name)
+            .await
+    }
+
+    /// Looks up the primary ENS name of `address` for use on `chain`, see [`Self::ens_client`].
+    pub async fn lookup_address(&self, chain: &str, address: Address) -> Result<Option<String>> {
+        self.ens_client(chain)
+            .ok_or_else(|| no_ens_client(chain))?
+            .lookup_address(address)
+            .await
+    }
+}
+
+fn no_ens_client(chain: &str) -> ClientError {
+    ClientError::Unsupported(format!(
+        "No ENS registry on chain {chain} or Ethereum mainnet"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bytes, address},
+        sol_types::SolCall,
+    };
+    use serde_json::Value;
+
+    use super::{addrCall, nameCall, resolverCall};
+    use crate::{
+        ClientError, EvmChainClient, EvmClientRegistry, ScriptedBackend, ScriptedResponse,
+        test_utils::{chain_metadata, eth_call_params, scripted_client},
+    };
+
+    const REGISTRY: Address = address!("0x00000000000000000000000000000000000E5E5a");
+    const RESOLVER: Address = address!("0x0000000000000000000000000000000000005e50");
+    const OWNER: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+
+    fn registry(chains: [(&str, u64, Option<Address>); 2]) -> EvmClientRegistry {
+        EvmClientRegistry::new(
+            chains
+                .into_iter()
+                .map(|(name, chain_id, ens_registry)| {
+                    let mut metadata = chain_metadata(name, chain_id);
+                    metadata.ens_registry = ens_registry;
+                    let client = scripted_client(metadata, &ScriptedBackend::new(), vec![]);
+                    (name.to_string(), client)
+                })
+                .collect(),
+        )
+    }
+
+    fn chain_id(client: Option<&EvmChainClient>) -> Option<u64> {
+        client.map(|client| client.metadata().chain_id)
+    }
+
+    #[test]
+    fn ens_client_falls_back_to_mainnet() {
+        let registry = registry([("eth", 1, None), ("base", 8453, None)]);
+        assert_eq!(chain_id(registry.ens_client("eth")), Some(1));
+        assert_eq!(chain_id(registry.ens_client("base")), Some(1));
+    }
+
+    #[tokio::test]
+    async fn ens_client_does_not_fall_back_to_other_chains() {
+        let registry = registry([("dev", 31337, Some(REGISTRY)), ("base", 8453, None)]);
+        assert_eq!(chain_id(registry.ens_client("dev")), Some(31337));
+        assert_eq!(chain_id(registry.ens_client("base")), None);
+        assert!(matches!(
+            registry.resolve_name("base", "vitalik.eth").await,
+            Err(ClientError::Unsupported(_))
+        ));
+    }
+
+    /// Returns a backend whose registry points every node at a resolver that answers `name` as
+    /// the reverse record and [`OWNER`] as the address of any name.
+    fn scripted_ens(name: &'static str) -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_call", move |params: &Value| {
+            let (to, data) = eth_call_params(params);
+            let output = match (to, &data[..4]) {
+                (REGISTRY, selector) if selector == resolverCall::SELECTOR => {
+                    resolverCall::abi_encode_returns(&RESOLVER)
+                }
+                (RESOLVER, selector) if selector == nameCall::SELECTOR => {
+                    nameCall::abi_encode_returns(&name.to_string())
+                }
+                (RESOLVER, selector) if selector == addrCall::SELECTOR => {
+                    addrCall::abi_encode_returns(&OWNER)
+                }
+                _ => panic!("unexpected call to {to}"),
+            };
+            ScriptedResponse::result(Bytes::from(output))
+        });
+        backend
+    }
+
+    #[tokio::test]
+    async fn omits_primary_names_it_cannot_normalize() {
+        let mut metadata = chain_metadata("dev", 31337);
+        metadata.ens_registry = Some(REGISTRY);
+
+        let client = scripted_client(metadata.clone(), &scripted_ens("alice.eth"), vec![]);
+        assert_eq!(
+            client.lookup_address(OWNER).await.unwrap().as_deref(),
+            Some("alice.eth")
+        );
+
+        let client = scripted_client(metadata, &scripted_ens("älice.eth"), vec![]);
+        assert_eq!(client.lookup_address(OWNER).await.unwrap(), None);
+    }
+}
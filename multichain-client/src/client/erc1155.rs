@@ -0,0 +1,308 @@
+//! ERC-1155 multi-token balances for [`EvmChainClient`].
+
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, Bytes, U256},
+    rpc::types::{Filter, Log},
+    sol,
+    sol_types::{SolCall, SolEvent},
+};
+use futures::future::{join_all, try_join_all};
+use tracing::warn;
+
+use crate::{
+    EvmChainClient,
+    error::{ClientError, Result},
+    metadata::{Erc1155Balance, Erc1155Holding, NftCollectionMetadata, NftStandard},
+};
+
+sol! {
+    function balanceOfBatch(address[] accounts, uint256[] ids) external view returns (uint256[]);
+
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+}
+
+/// Maximum number of token IDs read by a single `balanceOfBatch` call.
+const BALANCE_OF_BATCH_CHUNK_SIZE: usize = 200;
+
+impl EvmChainClient {
+    /// Fetches the ERC-1155 balances of `owner` in every tracked ERC-1155 collection.
+    ///
+    /// Only returns collections in which the address holds at least one token. Collections whose
+    /// balances could not be read are logged and skipped.
+    pub async fn get_erc1155_balances(&self, owner: Address) -> Vec<Erc1155Holding> {
+        self.get_erc1155_balances_at(owner, BlockId::latest()).await
+    }
+
+    /// Fetches the ERC-1155 balances of `owner` at the given block.
+    ///
+    /// See [`Self::get_erc1155_balances`].
+    pub async fn get_erc1155_balances_at(
+        &self,
+        owner: Address,
+        block: BlockId,
+    ) -> Vec<Erc1155Holding> {
+        let futures = self
+            .nft_collections()
+            .into_iter()
+            .filter(|collection| collection.standard == NftStandard::Erc1155)
+            .map(|collection| async move {
+                match self
+                    .get_erc1155_collection_balances(owner, &collection, block)
+                    .await
+                {
+                    Ok(balances) if balances.is_empty() => None,
+                    Ok(balances) => Some(Erc1155Holding {
+                        collection,
+                        balances,
+                    }),
+                    Err(err) => {
+                        warn!(
+                            "Error fetching ERC-1155 balances for {}: {err}",
+                            collection.symbol
+                        );
+                        None
+                    }
+                }
+            });
+
+        join_all(futures).await.into_iter().flatten().collect()
+    }
+
+    /// Returns the non-zero balances of `owner` in `collection` at the given block.
+    ///
+    /// Token IDs are discovered from `TransferSingle` and `TransferBatch` logs to `owner` since
+    /// the collection's `start_block`, then read with `balanceOfBatch`. Logs are scanned up to
+    /// the latest block; IDs received after `block` simply have a zero balance at `block`.
+    /// Discovered IDs are cached, so later lookups only scan the blocks mined since. Collections
+    /// without a `start_block` fail with [`ClientError::Config`].
+    pub async fn get_erc1155_collection_balances(
+        &self,
+        owner: Address,
+        collection: &NftCollectionMetadata,
+        block: BlockId,
+    ) -> Result<Vec<Erc1155Balance>> {
+        let filter = Filter::new()
+            .address(collection.address)
+            .event_signature(vec![
+                TransferSingle::SIGNATURE_HASH,
+                TransferBatch::SIGNATURE_HASH,
+            ])
+            .topic3(owner.into_word());
+
+        let token_ids: Vec<U256> = self
+            .discover_token_ids(owner, collection, &filter, received_token_ids)
+            .await?
+            .into_iter()
+            .collect();
+
+        let futures = token_ids
+            .chunks(BALANCE_OF_BATCH_CHUNK_SIZE)
+            .map(|ids| async move {
+                let call = balanceOfBatchCall {
+                    accounts: vec![owner; ids.len()],
+                    ids: ids.to_vec(),
+                };
+                let data = self
+                    .eth_call::<Bytes>(collection.address, call.abi_encode(), block)
+                    .await?;
+                let balances = balanceOfBatchCall::abi_decode_returns(&data)?;
+
+                Ok::<_, ClientError>(
+                    ids.iter()
+                        .zip(balances)
+                        .filter(|(_, balance)| !balance.is_zero())
+                        .map(|(&token_id, balance)| Erc1155Balance { token_id, balance })
+                        .collect::<Vec<_>>(),
+                )
+            });
+
+        Ok(try_join_all(futures).await?.into_iter().flatten().collect())
+    }
+}
+
+/// Returns the token IDs transferred by a `TransferSingle` or `TransferBatch` log.
+fn received_token_ids(log: &Log) -> Vec<U256> {
+    let data = &log.data().data;
+    match log.topic0() {
+        Some(&TransferSingle::SIGNATURE_HASH) => TransferSingle::abi_decode_data(data)
+            .map(|(id, _)| vec![id])
+            .unwrap_or_default(),
+        Some(&TransferBatch::SIGNATURE_HASH) => TransferBatch::abi_decode_data(data)
+            .map(|(ids, _)| ids)
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        eips::BlockId,
+        primitives::{Address, Bytes, U256, address},
+        rpc::types::{Filter, Log},
+        sol_types::{SolCall, SolEvent},
+    };
+    use serde_json::Value;
+
+    use super::{TransferBatch, TransferSingle, balanceOfBatchCall, received_token_ids};
+    use crate::{
+        ScriptedBackend, ScriptedResponse,
+        metadata::{NftCollectionMetadata, NftStandard},
+        test_utils::{chain_metadata, eth_call_params, scripted_client},
+    };
+
+    const COLLECTION: Address = address!("0x00000000000000000000000000000000000000cc");
+    const OWNER: Address = address!("0x00000000000000000000000000000000000000aa");
+    const START_BLOCK: u64 = 10;
+
+    fn collection() -> NftCollectionMetadata {
+        NftCollectionMetadata {
+            name: "Test collection".to_string(),
+            address: COLLECTION,
+            symbol: "TEST".to_string(),
+            standard: NftStandard::Erc1155,
+            start_block: Some(START_BLOCK),
+        }
+    }
+
+    fn log(event: &impl SolEvent) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: COLLECTION,
+                data: event.encode_log_data(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn single(id: u64) -> Log {
+        log(&TransferSingle {
+            operator: OWNER,
+            from: Address::ZERO,
+            to: OWNER,
+            id: U256::from(id),
+            value: U256::ONE,
+        })
+    }
+
+    fn batch(ids: impl IntoIterator<Item = u64>) -> Log {
+        let ids: Vec<U256> = ids.into_iter().map(U256::from).collect();
+        log(&TransferBatch {
+            operator: OWNER,
+            from: Address::ZERO,
+            to: OWNER,
+            values: vec![U256::ONE; ids.len()],
+            ids,
+        })
+    }
+
+    /// Scripts `balanceOfBatch` to return a balance of `id % 2` for every token ID.
+    fn scripted_balances(backend: &ScriptedBackend) {
+        backend.on_request("eth_call", |params: &Value| {
+            let (to, data) = eth_call_params(params);
+            assert_eq!(to, COLLECTION);
+            let call = balanceOfBatchCall::abi_decode(&data).unwrap();
+            assert!(call.accounts.iter().all(|&account| account == OWNER));
+            let balances: Vec<U256> = call.ids.iter().map(|id| id % U256::from(2)).collect();
+            ScriptedResponse::result(Bytes::from(balanceOfBatchCall::abi_encode_returns(
+                &balances,
+            )))
+        });
+    }
+
+    fn requested_range(params: &Value) -> (u64, u64) {
+        let filter: Filter = serde_json::from_value(params[0].clone()).unwrap();
+        (
+            filter.get_from_block().unwrap(),
+            filter.get_to_block().unwrap(),
+        )
+    }
+
+    #[test]
+    fn decodes_single_and_batch_transfers() {
+        assert_eq!(received_token_ids(&single(7)), [U256::from(7)]);
+        assert_eq!(
+            received_token_ids(&batch([1, 2, 3])),
+            [U256::from(1), U256::from(2), U256::from(3)]
+        );
+
+        let mut other = single(7);
+        other.inner.data.topics_mut()[0] = Default::default();
+        assert!(received_token_ids(&other).is_empty());
+    }
+
+    #[tokio::test]
+    async fn reads_discovered_ids_in_chunks() {
+        let backend = ScriptedBackend::new();
+        backend.on("eth_blockNumber", ScriptedResponse::result("0x64"));
+        // 250 distinct IDs, one of them received twice
+        backend.on(
+            "eth_getLogs",
+            ScriptedResponse::result(vec![single(1), batch(0..250)]),
+        );
+        scripted_balances(&backend);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let balances = client
+            .get_erc1155_collection_balances(OWNER, &collection(), BlockId::latest())
+            .await
+            .unwrap();
+        let token_ids: Vec<U256> = balances.iter().map(|balance| balance.token_id).collect();
+        let odd: Vec<U256> = (0..250).filter(|id| id % 2 == 1).map(U256::from).collect();
+        assert_eq!(token_ids, odd);
+        assert!(balances.iter().all(|balance| balance.balance == U256::ONE));
+
+        let chunks: Vec<usize> = backend
+            .requests()
+            .iter()
+            .filter(|request| request.method == "eth_call")
+            .map(|request| {
+                let (_, data) = eth_call_params(&request.params);
+                balanceOfBatchCall::abi_decode(&data).unwrap().ids.len()
+            })
+            .collect();
+        assert_eq!(chunks, [200, 50]);
+    }
+
+    #[tokio::test]
+    async fn later_lookups_only_scan_new_blocks() {
+        let backend = ScriptedBackend::new();
+        backend
+            .once("eth_blockNumber", ScriptedResponse::result("0x64"))
+            .on("eth_blockNumber", ScriptedResponse::result("0x96"));
+        backend.on_request("eth_getLogs", |params: &Value| {
+            let (from, _) = requested_range(params);
+            if from == START_BLOCK {
+                ScriptedResponse::result(vec![single(1)])
+            } else {
+                ScriptedResponse::result(vec![single(3)])
+            }
+        });
+        scripted_balances(&backend);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        for _ in 0..2 {
+            client
+                .get_erc1155_collection_balances(OWNER, &collection(), BlockId::latest())
+                .await
+                .unwrap();
+        }
+        let balances = client
+            .get_erc1155_collection_balances(OWNER, &collection(), BlockId::latest())
+            .await
+            .unwrap();
+        let token_ids: Vec<U256> = balances.iter().map(|balance| balance.token_id).collect();
+        assert_eq!(token_ids, [U256::from(1), U256::from(3)]);
+
+        let ranges: Vec<_> = backend
+            .requests()
+            .iter()
+            .filter(|request| request.method == "eth_getLogs")
+            .map(|request| requested_range(&request.params))
+            .collect();
+        // the third lookup finds no new blocks to scan
+        assert_eq!(ranges, [(START_BLOCK, 100), (101, 150)]);
+    }
+}
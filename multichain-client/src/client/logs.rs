@@ -0,0 +1,39 @@
+//! Event log queries for [`EvmChainClient`].
+
+use alloy::rpc::types::{Filter, Log};
+
+use crate::{
+    EvmChainClient,
+    error::Result,
+    log_range::{DEFAULT_LOG_CONCURRENCY, DEFAULT_MAX_LOG_RANGE, LogRangeFetcher},
+};
+
+impl EvmChainClient {
+    /// Returns a [`LogRangeFetcher`] using the chain's `maxLogRange` and `maxLogConcurrency`
+    /// settings.
+    pub fn log_fetcher(&self) -> LogRangeFetcher {
+        LogRangeFetcher::new(self.rpc_client.clone())
+            .with_max_range(self.metadata.max_log_range.unwrap_or(DEFAULT_MAX_LOG_RANGE))
+            .with_concurrency(
+                self.metadata
+                    .max_log_concurrency
+                    .unwrap_or(DEFAULT_LOG_CONCURRENCY),
+            )
+    }
+
+    /// Fetches the logs matching `filter` between `from_block` and `to_block` (inclusive).
+    ///
+    /// The range is fetched in chunks by [`Self::log_fetcher`], since most public RPCs reject
+    /// `eth_getLogs` requests spanning large block ranges. Use [`LogRangeFetcher::stream`] to
+    /// process the logs of long ranges incrementally.
+    pub async fn get_logs(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<Log>> {
+        self.log_fetcher()
+            .fetch(filter.clone(), from_block, to_block)
+            .await
+    }
+}
@@ -0,0 +1,481 @@
+//! ERC-721 NFT holdings for [`EvmChainClient`].
+
+use std::{collections::BTreeSet, num::NonZeroUsize, sync::MutexGuard};
+
+use alloy::{
+    eips::BlockId,
+    hex,
+    primitives::{Address, Bytes, FixedBytes, U256},
+    rpc::types::{Filter, Log},
+    sol,
+    sol_types::{SolCall, SolEvent},
+};
+use futures::future::join_all;
+use lru::LruCache;
+use serde_json::json;
+use tracing::warn;
+
+use crate::{
+    EvmChainClient,
+    batch::RpcCall,
+    error::{ClientError, Result},
+    metadata::{NftCollectionMetadata, NftHolding, NftStandard},
+};
+
+sol! {
+    function supportsInterface(bytes4 interfaceId) external view returns (bool);
+    function balanceOf(address owner) external view returns (uint256);
+    function tokenOfOwnerByIndex(address owner, uint256 index) external view returns (uint256);
+    function ownerOf(uint256 tokenId) external view returns (address);
+
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+}
+
+/// ERC-165 interface ID of `ERC721Enumerable`.
+const ERC721_ENUMERABLE_INTERFACE_ID: FixedBytes<4> = FixedBytes([0x78, 0x0e, 0x9d, 0x63]);
+
+/// Maximum number of (owner, collection) transfer log scans cached per chain.
+const MAX_CACHED_TOKEN_ID_SCANS: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+/// The token IDs of a collection discovered in the transfer logs to an owner.
+#[derive(Clone, Debug)]
+struct TokenIdScan {
+    token_ids: BTreeSet<U256>,
+    /// Last block whose logs were scanned
+    scanned_to: u64,
+}
+
+/// Caches the token IDs discovered from transfer logs per (owner, collection), so later lookups
+/// only scan the blocks mined since, evicting the least recently used entries.
+#[derive(Debug)]
+pub(crate) struct TokenIdScanCache(LruCache<(Address, Address), TokenIdScan>);
+
+impl Default for TokenIdScanCache {
+    fn default() -> Self {
+        Self(LruCache::new(MAX_CACHED_TOKEN_ID_SCANS))
+    }
+}
+
+impl EvmChainClient {
+    /// Fetches the NFTs owned by `owner` in every tracked ERC-721 collection.
+    ///
+    /// ERC-1155 collections are read by [`Self::get_erc1155_balances`].
+    ///
+    /// Only returns collections in which the address owns at least one token. Collections whose
+    /// holdings could not be read are logged and skipped.
+    pub async fn get_nft_holdings(&self, owner: Address) -> Vec<NftHolding> {
+        let futures = self
+            .nft_collections()
+            .into_iter()
+            .filter(|collection| collection.standard == NftStandard::Erc721)
+            .map(|collection| async move {
+                match self.get_nft_token_ids(owner, &collection).await {
+                    Ok(token_ids) if token_ids.is_empty() => None,
+                    Ok(token_ids) => Some(NftHolding {
+                        collection,
+                        token_ids,
+                    }),
+                    Err(err) => {
+                        warn!("Error fetching NFTs for {}: {err}", collection.symbol);
+                        None
+                    }
+                }
+            });
+
+        join_all(futures).await.into_iter().flatten().collect()
+    }
+
+    /// Returns the IDs of the tokens in `collection` owned by `owner`.
+    ///
+    /// Uses `tokenOfOwnerByIndex` when the collection supports `ERC721Enumerable`. Otherwise,
+    /// candidate token IDs are collected from `Transfer` logs to `owner` since the collection's
+    /// `start_block`, and kept if `ownerOf` still returns `owner`; collections without a
+    /// `start_block` fail with [`ClientError::Config`] rather than scanning from genesis.
+    /// Candidates are cached, so later lookups only scan the blocks mined since.
+    pub async fn get_nft_token_ids(
+        &self,
+        owner: Address,
+        collection: &NftCollectionMetadata,
+    ) -> Result<Vec<U256>> {
+        if self.supports_erc721_enumerable(collection.address).await? {
+            self.get_nft_token_ids_enumerable(owner, collection.address)
+                .await
+        } else {
+            self.get_nft_token_ids_from_logs(owner, collection).await
+        }
+    }
+
+    async fn supports_erc721_enumerable(&self, collection: Address) -> Result<bool> {
+        let call = supportsInterfaceCall {
+            interfaceId: ERC721_ENUMERABLE_INTERFACE_ID,
+        };
+        match self
+            .eth_call::<Bytes>(collection, call.abi_encode(), BlockId::latest())
+            .await
+        {
+            Ok(data) => Ok(supportsInterfaceCall::abi_decode_returns(&data).unwrap_or(false)),
+            // contracts without ERC-165 revert
+            Err(ClientError::Reverted(_) | ClientError::Rpc { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn get_nft_token_ids_enumerable(
+        &self,
+        owner: Address,
+        collection: Address,
+    ) -> Result<Vec<U256>> {
+        let balance = self
+            .eth_call::<U256>(
+                collection,
+                balanceOfCall { owner }.abi_encode(),
+                BlockId::latest(),
+            )
+            .await?;
+        let balance = u64::try_from(balance)
+            .map_err(|_| ClientError::Decode(format!("Invalid NFT balance: {balance}")))?;
+
+        let calls = (0..balance)
+            .map(|index| {
+                let data = tokenOfOwnerByIndexCall {
+                    owner,
+                    index: U256::from(index),
+                }
+                .abi_encode();
+                eth_call_request(collection, &data)
+            })
+            .collect();
+
+        self.batch_request(calls)
+            .await
+            .into_iter()
+            .map(|result| Ok(serde_json::from_value(result?)?))
+            .collect()
+    }
+
+    async fn get_nft_token_ids_from_logs(
+        &self,
+        owner: Address,
+        collection: &NftCollectionMetadata,
+    ) -> Result<Vec<U256>> {
+        let filter = Filter::new()
+            .address(collection.address)
+            .event_signature(Transfer::SIGNATURE_HASH)
+            .topic2(owner.into_word());
+
+        let candidates: Vec<U256> = self
+            .discover_token_ids(owner, collection, &filter, transferred_token_id)
+            .await?
+            .into_iter()
+            .collect();
+
+        let calls = candidates
+            .iter()
+            .map(|&token_id| {
+                let data = ownerOfCall { tokenId: token_id }.abi_encode();
+                eth_call_request(collection.address, &data)
+            })
+            .collect();
+        let owners = self.batch_request(calls).await;
+
+        candidates
+            .into_iter()
+            .zip(owners)
+            .filter_map(|(token_id, result)| match result {
+                Ok(value) => match serde_json::from_value::<Bytes>(value) {
+                    Ok(data) => match ownerOfCall::abi_decode_returns(&data) {
+                        Ok(current) if current == owner => Some(Ok(token_id)),
+                        Ok(_) => None,
+                        Err(err) => Some(Err(err.into())),
+                    },
+                    Err(err) => Some(Err(err.into())),
+                },
+                // burned tokens revert in `ownerOf`
+                Err(ClientError::Reverted(_) | ClientError::Rpc { .. }) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect()
+    }
+}
+
+impl EvmChainClient {
+    /// Returns the IDs of the tokens of `collection` that `owner` ever received, read by
+    /// `token_ids` from the logs matching `filter`.
+    ///
+    /// The first lookup scans from the collection's `start_block` to the latest block; later
+    /// lookups only scan the blocks mined since and add to the IDs already found.
+    pub(super) async fn discover_token_ids(
+        &self,
+        owner: Address,
+        collection: &NftCollectionMetadata,
+        filter: &Filter,
+        token_ids: fn(&Log) -> Vec<U256>,
+    ) -> Result<BTreeSet<U256>> {
+        let key = (owner, collection.address);
+        let cached = self.token_id_scans().0.get(&key).cloned();
+        let (mut scan, from) = match cached {
+            Some(scan) => {
+                let from = scan.scanned_to + 1;
+                (scan, from)
+            }
+            None => {
+                let from = log_scan_start(collection)?;
+                let scan = TokenIdScan {
+                    token_ids: BTreeSet::new(),
+                    scanned_to: from.saturating_sub(1),
+                };
+                (scan, from)
+            }
+        };
+
+        let latest = self.get_block_number().await?;
+        if from <= latest {
+            let logs = self.get_logs(filter, from, latest).await?;
+            scan.token_ids.extend(logs.iter().flat_map(token_ids));
+            scan.scanned_to = latest;
+            self.token_id_scans().0.put(key, scan.clone());
+        }
+        Ok(scan.token_ids)
+    }
+
+    fn token_id_scans(&self) -> MutexGuard<'_, TokenIdScanCache> {
+        self.token_id_scans
+            .lock()
+            .expect("token ID scan cache lock poisoned")
+    }
+}
+
+/// Returns the first block of the transfer log scans used to discover the token IDs of
+/// `collection`.
+pub(super) fn log_scan_start(collection: &NftCollectionMetadata) -> Result<u64> {
+    collection.start_block.ok_or_else(|| {
+        ClientError::Config(format!(
+            "Collection {} needs a startBlock to discover token IDs from transfer logs",
+            collection.symbol
+        ))
+    })
+}
+
+/// Returns the token ID transferred by an ERC-721 `Transfer` log.
+///
+/// ERC-20 transfers share the signature but do not index the third argument, so their logs have
+/// no token ID topic.
+fn transferred_token_id(log: &Log) -> Vec<U256> {
+    log.topics()
+        .get(3)
+        .map(|topic| U256::from_be_bytes(topic.0))
+        .into_iter()
+        .collect()
+}
+
+/// Builds an `eth_call` request against the latest block for use in a JSON-RPC batch.
+fn eth_call_request(to: Address, data: &[u8]) -> RpcCall {
+    RpcCall::new(
+        "eth_call",
+        json!([{ "to": to, "data": format!("0x{}", hex::encode(data)) }, "latest"]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, B256, Bytes, LogData, U256, address},
+        rpc::types::{Filter, Log},
+        sol_types::{SolCall, SolEvent},
+    };
+    use serde_json::Value;
+
+    use super::{
+        Transfer, balanceOfCall, ownerOfCall, supportsInterfaceCall, tokenOfOwnerByIndexCall,
+    };
+    use crate::{
+        ClientError, ScriptedBackend, ScriptedResponse,
+        metadata::{NftCollectionMetadata, NftStandard},
+        test_utils::{chain_metadata, eth_call_params, scripted_client, word},
+    };
+
+    const COLLECTION: Address = address!("0x00000000000000000000000000000000000000cc");
+    const OWNER: Address = address!("0x00000000000000000000000000000000000000aa");
+    const OTHER: Address = address!("0x00000000000000000000000000000000000000bb");
+    const START_BLOCK: u64 = 10;
+    /// Token still owned by [`OWNER`]
+    const HELD: u64 = 1;
+    /// Token since transferred to [`OTHER`]
+    const SOLD: u64 = 2;
+    /// Token since burned, so `ownerOf` reverts
+    const BURNED: u64 = 3;
+
+    fn collection(start_block: Option<u64>) -> NftCollectionMetadata {
+        NftCollectionMetadata {
+            name: "Test collection".to_string(),
+            address: COLLECTION,
+            symbol: "TEST".to_string(),
+            standard: NftStandard::Erc721,
+            start_block,
+        }
+    }
+
+    fn log(data: LogData) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: COLLECTION,
+                data,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn transfer(token_id: u64) -> Log {
+        log(Transfer {
+            from: Address::ZERO,
+            to: OWNER,
+            tokenId: U256::from(token_id),
+        }
+        .encode_log_data())
+    }
+
+    /// An ERC-20 `Transfer` log, which leaves the amount unindexed.
+    fn erc20_transfer() -> Log {
+        log(LogData::new_unchecked(
+            vec![Transfer::SIGNATURE_HASH, B256::ZERO, OWNER.into_word()],
+            word(7),
+        ))
+    }
+
+    fn requested_range(params: &Value) -> (u64, u64) {
+        let filter: Filter = serde_json::from_value(params[0].clone()).unwrap();
+        (
+            filter.get_from_block().unwrap(),
+            filter.get_to_block().unwrap(),
+        )
+    }
+
+    /// Scripts a collection answering `supportsInterface` with `enumerable`, holding
+    /// [`HELD`] and [`SOLD`] by index for [`OWNER`], and the owners of every other token.
+    fn scripted_collection(enumerable: bool) -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on("eth_blockNumber", ScriptedResponse::result("0x64"));
+        backend.on_request("eth_call", move |params: &Value| {
+            let (to, data) = eth_call_params(params);
+            assert_eq!(to, COLLECTION);
+            let reverted = ScriptedResponse::error(3, "execution reverted");
+            match data[..4].try_into().unwrap() {
+                supportsInterfaceCall::SELECTOR => ScriptedResponse::result(Bytes::from(
+                    supportsInterfaceCall::abi_encode_returns(&enumerable),
+                )),
+                balanceOfCall::SELECTOR if enumerable => ScriptedResponse::result(word(2)),
+                tokenOfOwnerByIndexCall::SELECTOR if enumerable => {
+                    let call = tokenOfOwnerByIndexCall::abi_decode(&data).unwrap();
+                    assert_eq!(call.owner, OWNER);
+                    ScriptedResponse::result(word(10 + call.index.to::<u64>()))
+                }
+                ownerOfCall::SELECTOR => {
+                    let call = ownerOfCall::abi_decode(&data).unwrap();
+                    let owner = match call.tokenId.to::<u64>() {
+                        HELD => OWNER,
+                        SOLD => OTHER,
+                        _ => return reverted,
+                    };
+                    ScriptedResponse::result(Bytes::from(ownerOfCall::abi_encode_returns(&owner)))
+                }
+                _ => reverted,
+            }
+        });
+        backend
+    }
+
+    #[tokio::test]
+    async fn enumerable_collections_are_read_by_index() {
+        let backend = scripted_collection(true);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        // no start block is needed without a log scan
+        let token_ids = client
+            .get_nft_token_ids(OWNER, &collection(None))
+            .await
+            .unwrap();
+        assert_eq!(token_ids, [U256::from(10), U256::from(11)]);
+        assert!(
+            backend
+                .requests()
+                .iter()
+                .all(|request| request.method == "eth_call")
+        );
+    }
+
+    #[tokio::test]
+    async fn log_candidates_are_filtered_by_current_owner() {
+        let backend = scripted_collection(false);
+        backend.on(
+            "eth_getLogs",
+            ScriptedResponse::result(vec![
+                transfer(HELD),
+                transfer(SOLD),
+                transfer(BURNED),
+                erc20_transfer(),
+            ]),
+        );
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let token_ids = client
+            .get_nft_token_ids(OWNER, &collection(Some(START_BLOCK)))
+            .await
+            .unwrap();
+        assert_eq!(token_ids, [U256::from(HELD)]);
+
+        // the ERC-20 transfer's amount is not mistaken for a token ID
+        let owner_of_calls = backend
+            .requests()
+            .iter()
+            .filter(|request| request.method == "eth_call")
+            .filter(|request| {
+                let (_, data) = eth_call_params(&request.params);
+                data.starts_with(&ownerOfCall::SELECTOR)
+            })
+            .count();
+        assert_eq!(owner_of_calls, 3);
+    }
+
+    #[tokio::test]
+    async fn later_log_scans_start_after_the_last_one() {
+        let backend = scripted_collection(false);
+        backend
+            .once("eth_blockNumber", ScriptedResponse::result("0x64"))
+            .on("eth_blockNumber", ScriptedResponse::result("0x96"));
+        backend.on_request("eth_getLogs", |params: &Value| {
+            let (from, _) = requested_range(params);
+            if from == START_BLOCK {
+                ScriptedResponse::result(vec![transfer(SOLD)])
+            } else {
+                ScriptedResponse::result(vec![transfer(HELD)])
+            }
+        });
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+        let collection = collection(Some(START_BLOCK));
+
+        let token_ids = client.get_nft_token_ids(OWNER, &collection).await.unwrap();
+        assert!(token_ids.is_empty());
+        let token_ids = client.get_nft_token_ids(OWNER, &collection).await.unwrap();
+        assert_eq!(token_ids, [U256::from(HELD)]);
+
+        let ranges: Vec<_> = backend
+            .requests()
+            .iter()
+            .filter(|request| request.method == "eth_getLogs")
+            .map(|request| requested_range(&request.params))
+            .collect();
+        assert_eq!(ranges, [(START_BLOCK, 100), (101, 150)]);
+    }
+
+    #[tokio::test]
+    async fn log_scans_need_a_start_block() {
+        let backend = scripted_collection(false);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client
+            .get_nft_token_ids(OWNER, &collection(None))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Config(_)));
+    }
+}
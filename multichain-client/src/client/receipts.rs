@@ -0,0 +1,561 @@
+//! Transaction and receipt lookups for [`EvmChainClient`].
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{Address, B256, Bytes, U8, U64, U128, U256, utils::format_units},
+    rpc::types::Log,
+};
+use foundry_block_explorers::account::NormalTransaction;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::{
+    EvmChainClient,
+    batch::RpcCall,
+    error::{ClientError, Result},
+    metadata::{FeeAmount, L2FeeModel, TransactionDetails, TransactionFees, TransactionStatus},
+};
+
+/// The subset of an `eth_getTransactionByHash` response needed for [`TransactionDetails`].
+///
+/// Read field by field rather than as an alloy transaction, so L2-specific transaction types
+/// (e.g., OP-stack deposits) do not fail to deserialize.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTransaction {
+    hash: B256,
+    #[serde(rename = "type", default)]
+    tx_type: U8,
+    from: Address,
+    to: Option<Address>,
+    value: U256,
+    nonce: U64,
+    input: Bytes,
+    gas: U64,
+    gas_price: Option<U128>,
+    block_number: Option<U64>,
+}
+
+/// The subset of an `eth_getTransactionReceipt` response needed for [`TransactionDetails`],
+/// including the L1 data fee fields of OP-stack and Arbitrum receipts.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcReceipt {
+    status: Option<U64>,
+    block_number: U64,
+    gas_used: U64,
+    effective_gas_price: Option<U128>,
+    blob_gas_used: Option<U64>,
+    blob_gas_price: Option<U128>,
+    contract_address: Option<Address>,
+    logs: Vec<Log>,
+    l1_fee: Option<U256>,
+    l1_gas_used: Option<U64>,
+    l1_gas_price: Option<U128>,
+    gas_used_for_l1: Option<U64>,
+}
+
+/// The L1 data fee of an L2 transaction.
+#[derive(Debug, Default)]
+struct L1DataFee {
+    /// Fee paid, in wei
+    fee: U256,
+    /// Share of the receipt's `gasUsed` charged for the fee, which is non-zero on Arbitrum
+    l2_gas: u64,
+}
+
+impl EvmChainClient {
+    /// Fetches a transaction along with its receipt, and breaks down the fees it paid.
+    ///
+    /// The transaction and receipt are fetched in one batch, followed by the header of the
+    /// block it was mined in for the base fee. Pending transactions are returned without a
+    /// receipt. Fails with [`ClientError::NotFound`] if the node does not know the transaction.
+    pub async fn get_transaction_details(&self, hash: B256) -> Result<TransactionDetails> {
+        let calls = vec![
+            RpcCall::new("eth_getTransactionByHash", json!([hash])),
+            RpcCall::new("eth_getTransactionReceipt", json!([hash])),
+        ];
+        let [tx, receipt]: [Result<Value>; 2] =
+            self.batch_request(calls).await.try_into().map_err(|_| {
+                ClientError::Decode("Unexpected number of batch responses".to_string())
+            })?;
+
+        let tx: RpcTransaction = serde_json::from_value::<Option<_>>(tx?)?.ok_or_else(|| {
+            ClientError::NotFound(format!("Transaction {hash} on {}", self.metadata.name))
+        })?;
+        let receipt: Option<RpcReceipt> = serde_json::from_value(receipt?)?;
+
+        let mut details = TransactionDetails {
+            hash: tx.hash,
+            status: TransactionStatus::Pending,
+            block_number: tx.block_number.map(|number| number.to()),
+            tx_type: tx.tx_type.to(),
+            from: tx.from,
+            to: tx.to,
+            contract_address: None,
+            value: tx.value,
+            nonce: tx.nonce.to(),
+            input: tx.input,
+            gas_limit: tx.gas.to(),
+            gas_used: None,
+            fees: None,
+            logs: Vec::new(),
+        };
+        let Some(receipt) = receipt else {
+            return Ok(details);
+        };
+
+        let header = self
+            .get_block_header(BlockNumberOrTag::Number(receipt.block_number.to()))
+            .await?;
+        let base_fee = header.base_fee_per_gas.map(|fee| fee.to());
+        let gas_price = receipt
+            .effective_gas_price
+            .or(tx.gas_price)
+            .unwrap_or_default()
+            .to();
+
+        details.status = match receipt.status {
+            Some(status) if status.is_zero() => TransactionStatus::Failed,
+            Some(_) => TransactionStatus::Success,
+            None => TransactionStatus::Unknown,
+        };
+        details.block_number = Some(receipt.block_number.to());
+        details.contract_address = receipt.contract_address;
+        details.gas_used = Some(receipt.gas_used.to());
+        details.fees = Some(self.transaction_fees(&receipt, gas_price, base_fee));
+        details.logs = receipt.logs;
+        Ok(details)
+    }
+
+    /// Returns the total fee paid by each of `transactions`, in order, or `None` where it could
+    /// not be determined.
+    ///
+    /// Explorer rows only report L2 gas, so on chains with an `l2FeeModel` the receipts are
+    /// fetched in batches to add the L1 data fee. Elsewhere the fee is computed from the rows.
+    pub async fn get_transaction_costs(
+        &self,
+        transactions: &[NormalTransaction],
+    ) -> Vec<Option<FeeAmount>> {
+        let row_cost = |tx: &NormalTransaction| {
+            tx.gas_price
+                .map(|gas_price| self.native_amount(tx.gas_used * gas_price))
+        };
+        if self.metadata.l2_fee_model.is_none() {
+            return transactions.iter().map(row_cost).collect();
+        }
+
+        let calls = transactions
+            .iter()
+            .map(|tx| RpcCall::new("eth_getTransactionReceipt", json!([tx.hash.value()])))
+            .collect();
+        let receipts = self.batch_request(calls).await;
+
+        transactions
+            .iter()
+            .zip(receipts)
+            .map(|(tx, receipt)| {
+                let receipt = receipt
+                    .and_then(|receipt| Ok(serde_json::from_value::<Option<RpcReceipt>>(receipt)?));
+                match receipt {
+                    Ok(Some(receipt)) => {
+                        let gas_price = receipt
+                            .effective_gas_price
+                            .map(U256::from)
+                            .or(tx.gas_price)
+                            .unwrap_or_default();
+                        let l1 = self
+                            .l1_data_fee(&receipt, gas_price.to())
+                            .unwrap_or_default();
+                        let execution_gas =
+                            U256::from(receipt.gas_used.to::<u64>().saturating_sub(l1.l2_gas));
+                        Some(self.native_amount(execution_gas * gas_price + l1.fee))
+                    }
+                    Ok(None) => row_cost(tx),
+                    Err(err) => {
+                        warn!(
+                            "Failed to fetch the receipt of {} on {}: {err}",
+                            tx.hash.value().copied().unwrap_or_default(),
+                            self.metadata.name
+                        );
+                        row_cost(tx)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Splits the fees paid by a mined transaction into the burned base fee, the priority tip,
+    /// the blob fee and the L1 data fee.
+    fn transaction_fees(
+        &self,
+        receipt: &RpcReceipt,
+        effective_gas_price: u128,
+        base_fee_per_gas: Option<u128>,
+    ) -> TransactionFees {
+        let l1 = self.l1_data_fee(receipt, effective_gas_price);
+        let execution_gas = U256::from(
+            receipt
+                .gas_used
+                .to::<u64>()
+                .saturating_sub(l1.as_ref().map_or(0, |l1| l1.l2_gas)),
+        );
+        let priority_fee_per_gas =
+            effective_gas_price.saturating_sub(base_fee_per_gas.unwrap_or_default());
+
+        let burned = U256::from(base_fee_per_gas.unwrap_or_default()) * execution_gas;
+        let tip = U256::from(priority_fee_per_gas) * execution_gas;
+        let blob = receipt
+            .blob_gas_used
+            .zip(receipt.blob_gas_price)
+            .map(|(used, price)| U256::from(used) * U256::from(price));
+        let l1 = l1.map(|l1| l1.fee);
+        let total = burned + tip + blob.unwrap_or_default() + l1.unwrap_or_default();
+
+        let op_stack = self.metadata.l2_fee_model == Some(L2FeeModel::OpStack);
+        let arbitrum = self.metadata.l2_fee_model == Some(L2FeeModel::Arbitrum);
+        TransactionFees {
+            effective_gas_price,
+            base_fee_per_gas,
+            priority_fee_per_gas,
+            blob_gas_used: receipt.blob_gas_used.map(|used| used.to()),
+            blob_gas_price: receipt.blob_gas_price.map(|price| price.to()),
+            l1_gas_used: receipt
+                .l1_gas_used
+                .filter(|_| op_stack)
+                .map(|used| used.to()),
+            l1_gas_price: receipt
+                .l1_gas_price
+                .filter(|_| op_stack)
+                .map(|price| price.to()),
+            gas_used_for_l1: receipt
+                .gas_used_for_l1
+                .filter(|_| arbitrum)
+                .map(|used| used.to()),
+            total: self.native_amount(total),
+            burned: self.native_amount(burned),
+            tip: self.native_amount(tip),
+            blob: blob.map(|blob| self.native_amount(blob)),
+            l1: l1.map(|l1| self.native_amount(l1)),
+        }
+    }
+
+    /// Reads the L1 data fee from a receipt according to the chain's `l2FeeModel`.
+    ///
+    /// Returns `None` on L1 chains, and for receipts without the fee fields, such as OP-stack
+    /// deposit transactions.
+    fn l1_data_fee(&self, receipt: &RpcReceipt, effective_gas_price: u128) -> Option<L1DataFee> {
+        match self.metadata.l2_fee_model? {
+            L2FeeModel::OpStack => Some(L1DataFee {
+                fee: receipt.l1_fee?,
+                l2_gas: 0,
+            }),
+            L2FeeModel::Arbitrum => {
+                let l2_gas = receipt.gas_used_for_l1?.to::<u64>();
+                Some(L1DataFee {
+                    fee: U256::from(l2_gas) * U256::from(effective_gas_price),
+                    l2_gas,
+                })
+            }
+        }
+    }
+
+    /// Formats an amount of the native currency with its decimals.
+    fn native_amount(&self, value: U256) -> FeeAmount {
+        let amount = u8::try_from(self.metadata.native_currency.decimals)
+            .ok()
+            .and_then(|decimals| format_units(value, decimals).ok())
+            .unwrap_or_else(|| value.to_string());
+        FeeAmount { value, amount }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+    use foundry_block_explorers::account::NormalTransaction;
+    use serde_json::{Value, json};
+
+    use super::RpcReceipt;
+    use crate::{
+        EvmChainClient, ScriptedBackend, ScriptedResponse,
+        metadata::{ChainMetaData, L2FeeModel, TransactionFees},
+        test_utils::{chain_metadata, scripted_client},
+    };
+
+    const GWEI: u128 = 1_000_000_000;
+
+    /// A receipt from an OP-stack chain, with 46,325 gas at 1,000,050 wei and an `l1Fee`.
+    const OP_STACK_RECEIPT: &str = include_str!("../../fixtures/op-stack-receipt.json");
+    const OP_STACK_GAS_USED: u128 = 46_325;
+    const OP_STACK_GAS_PRICE: u128 = 1_000_050;
+    const OP_STACK_L1_FEE: u128 = 11_390_927_316;
+
+    /// A receipt from Arbitrum, with 107,512 gas at 0.01 gwei, 27,420 of which for L1 data.
+    const ARBITRUM_RECEIPT: &str = include_str!("../../fixtures/arbitrum-receipt.json");
+    const ARBITRUM_GAS_USED: u128 = 107_512;
+    const ARBITRUM_GAS_USED_FOR_L1: u128 = 27_420;
+    const ARBITRUM_GAS_PRICE: u128 = GWEI / 100;
+
+    fn client(metadata: ChainMetaData) -> EvmChainClient {
+        scripted_client(metadata, &ScriptedBackend::new(), vec![])
+    }
+
+    /// Parses a mined receipt using 21,000 gas, with the given extra fields.
+    fn receipt(fields: Value) -> RpcReceipt {
+        let mut receipt = json!({
+            "status": "0x1",
+            "blockNumber": "0x10",
+            "gasUsed": "0x5208",
+            "logs": [],
+        });
+        receipt
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(receipt).unwrap()
+    }
+
+    fn assert_total(fees: &TransactionFees) {
+        let parts = [&fees.burned, &fees.tip]
+            .into_iter()
+            .chain(&fees.blob)
+            .chain(&fees.l1)
+            .map(|amount| amount.value)
+            .sum::<U256>();
+        assert_eq!(fees.total.value, parts);
+    }
+
+    #[test]
+    fn legacy_fees_are_all_tip() {
+        let client = client(chain_metadata("test", 1));
+        let fees = client.transaction_fees(&receipt(json!({})), 20 * GWEI, None);
+
+        assert_eq!(fees.base_fee_per_gas, None);
+        assert_eq!(fees.priority_fee_per_gas, 20 * GWEI);
+        assert_eq!(fees.burned.value, U256::ZERO);
+        assert_eq!(fees.tip.value, U256::from(21_000 * 20 * GWEI));
+        assert_eq!(fees.total.amount, "0.000420000000000000");
+        assert!(fees.blob.is_none());
+        assert!(fees.l1.is_none());
+        assert_total(&fees);
+    }
+
+    #[test]
+    fn eip1559_fees_split_base_fee_and_tip() {
+        let client = client(chain_metadata("test", 1));
+        let fees = client.transaction_fees(
+            &receipt(json!({ "effectiveGasPrice": "0x77359400" })),
+            2 * GWEI,
+            Some(15 * GWEI / 10),
+        );
+
+        assert_eq!(fees.priority_fee_per_gas, GWEI / 2);
+        assert_eq!(fees.burned.value, U256::from(21_000 * 15 * GWEI / 10));
+        assert_eq!(fees.tip.value, U256::from(21_000 * GWEI / 2));
+        assert_eq!(fees.total.value, U256::from(21_000 * 2 * GWEI));
+        assert!(fees.blob.is_none());
+        assert_total(&fees);
+    }
+
+    #[test]
+    fn blob_fees_are_added_to_the_total() {
+        let client = client(chain_metadata("test", 1));
+        let fees = client.transaction_fees(
+            &receipt(json!({
+                "blobGasUsed": "0x20000",
+                "blobGasPrice": "0x3",
+            })),
+            2 * GWEI,
+            Some(GWEI),
+        );
+
+        assert_eq!(fees.blob_gas_used, Some(131_072));
+        assert_eq!(fees.blob_gas_price, Some(3));
+        assert_eq!(fees.blob.as_ref().unwrap().value, U256::from(131_072 * 3));
+        assert_eq!(
+            fees.total.value,
+            U256::from(21_000 * 2 * GWEI + 131_072 * 3)
+        );
+        assert_total(&fees);
+    }
+
+    #[test]
+    fn l2_fields_are_ignored_on_l1() {
+        let client = client(chain_metadata("test", 1));
+        let fees = client.transaction_fees(
+            &receipt(json!({ "l1Fee": "0x100", "gasUsedForL1": "0x10" })),
+            GWEI,
+            Some(GWEI),
+        );
+
+        assert!(fees.l1.is_none());
+        assert_eq!(fees.gas_used_for_l1, None);
+        assert_eq!(fees.total.value, U256::from(21_000 * GWEI));
+    }
+
+    #[test]
+    fn native_amounts_use_the_currency_decimals() {
+        let client = client(chain_metadata("test", 1));
+        let amount = client.native_amount(U256::from(2_100_000_000_000_000u64));
+        assert_eq!(amount.value, U256::from(2_100_000_000_000_000u64));
+        assert_eq!(amount.amount, "0.002100000000000000");
+
+        let mut metadata = chain_metadata("test", 1);
+        metadata.native_currency.decimals = 6;
+        assert_eq!(client_amount(metadata, 1_500_000), "1.500000");
+
+        // decimals that cannot be formatted fall back to the raw amount
+        let mut metadata = chain_metadata("test", 1);
+        metadata.native_currency.decimals = 300;
+        assert_eq!(client_amount(metadata, 1_500_000), "1500000");
+    }
+
+    fn client_amount(metadata: ChainMetaData, value: u64) -> String {
+        client(metadata).native_amount(U256::from(value)).amount
+    }
+
+    fn l2_metadata(model: L2FeeModel) -> ChainMetaData {
+        let mut metadata = chain_metadata("l2", 10);
+        metadata.l2_fee_model = Some(model);
+        metadata
+    }
+
+    /// Returns the hash of a receipt fixture.
+    fn receipt_hash(receipt: &str) -> B256 {
+        let receipt: Value = serde_json::from_str(receipt).unwrap();
+        serde_json::from_value(receipt["transactionHash"].clone()).unwrap()
+    }
+
+    /// Returns an explorer row for `hash`, which only reports L2 gas.
+    fn explorer_row(hash: B256, gas_used: u128, gas_price: u128) -> NormalTransaction {
+        serde_json::from_value(json!({
+            "blockNumber": "1000",
+            "timeStamp": "1735689624",
+            "hash": hash,
+            "nonce": "1",
+            "blockHash": B256::ZERO,
+            "transactionIndex": "0",
+            "from": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+            "to": "0x4200000000000000000000000000000000000006",
+            "value": "0",
+            "gas": "100000",
+            "gasPrice": gas_price.to_string(),
+            "isError": "0",
+            "txreceipt_status": "1",
+            "input": "0x",
+            "contractAddress": "",
+            "cumulativeGasUsed": gas_used.to_string(),
+            "gasUsed": gas_used.to_string(),
+            "confirmations": "1",
+            "methodId": "0x",
+            "functionName": "",
+        }))
+        .unwrap()
+    }
+
+    /// Scripts `eth_getTransactionReceipt` to answer with `receipt` for its hash, and with
+    /// `null` for any other transaction.
+    fn scripted_receipt(receipt: &'static str) -> ScriptedBackend {
+        let hash = receipt_hash(receipt);
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getTransactionReceipt", move |params: &Value| {
+            if params[0] == json!(hash) {
+                ScriptedResponse::Result(serde_json::from_str(receipt).unwrap())
+            } else {
+                ScriptedResponse::result(Value::Null)
+            }
+        });
+        backend
+    }
+
+    #[test]
+    fn op_stack_fees_add_the_l1_fee() {
+        let client = client(l2_metadata(L2FeeModel::OpStack));
+        let receipt: RpcReceipt = serde_json::from_str(OP_STACK_RECEIPT).unwrap();
+        let fees = client.transaction_fees(&receipt, OP_STACK_GAS_PRICE, Some(1_000_000));
+
+        assert_eq!(fees.l1.as_ref().unwrap().value, U256::from(OP_STACK_L1_FEE));
+        assert_eq!(fees.l1_gas_used, Some(1_600));
+        assert_eq!(fees.l1_gas_price, Some(10 * GWEI));
+        assert_eq!(
+            fees.total.value,
+            U256::from(OP_STACK_GAS_USED * OP_STACK_GAS_PRICE + OP_STACK_L1_FEE)
+        );
+        assert_total(&fees);
+    }
+
+    #[test]
+    fn arbitrum_fees_split_out_the_l1_gas() {
+        let client = client(l2_metadata(L2FeeModel::Arbitrum));
+        let receipt: RpcReceipt = serde_json::from_str(ARBITRUM_RECEIPT).unwrap();
+        let fees = client.transaction_fees(&receipt, ARBITRUM_GAS_PRICE, Some(ARBITRUM_GAS_PRICE));
+
+        assert_eq!(fees.gas_used_for_l1, Some(ARBITRUM_GAS_USED_FOR_L1 as u64));
+        assert_eq!(
+            fees.l1.as_ref().unwrap().value,
+            U256::from(ARBITRUM_GAS_USED_FOR_L1 * ARBITRUM_GAS_PRICE)
+        );
+        assert_eq!(
+            fees.burned.value,
+            U256::from((ARBITRUM_GAS_USED - ARBITRUM_GAS_USED_FOR_L1) * ARBITRUM_GAS_PRICE)
+        );
+        // the L1 share is already part of `gasUsed`
+        assert_eq!(
+            fees.total.value,
+            U256::from(ARBITRUM_GAS_USED * ARBITRUM_GAS_PRICE)
+        );
+        assert_total(&fees);
+    }
+
+    #[tokio::test]
+    async fn op_stack_costs_add_the_l1_fee_from_receipts() {
+        let backend = scripted_receipt(OP_STACK_RECEIPT);
+        let client = scripted_client(l2_metadata(L2FeeModel::OpStack), &backend, vec![]);
+        let transactions = [
+            explorer_row(
+                receipt_hash(OP_STACK_RECEIPT),
+                OP_STACK_GAS_USED,
+                OP_STACK_GAS_PRICE,
+            ),
+            // without a receipt, the cost is read from the row
+            explorer_row(B256::with_last_byte(1), 21_000, GWEI),
+        ];
+
+        let costs = client.get_transaction_costs(&transactions).await;
+        assert_eq!(
+            costs[0].as_ref().unwrap().value,
+            U256::from(OP_STACK_GAS_USED * OP_STACK_GAS_PRICE + OP_STACK_L1_FEE)
+        );
+        assert_eq!(costs[1].as_ref().unwrap().value, U256::from(21_000 * GWEI));
+    }
+
+    #[tokio::test]
+    async fn arbitrum_costs_match_the_receipt_gas() {
+        let backend = scripted_receipt(ARBITRUM_RECEIPT);
+        let client = scripted_client(l2_metadata(L2FeeModel::Arbitrum), &backend, vec![]);
+        let transactions = [explorer_row(
+            receipt_hash(ARBITRUM_RECEIPT),
+            ARBITRUM_GAS_USED,
+            ARBITRUM_GAS_PRICE,
+        )];
+
+        let costs = client.get_transaction_costs(&transactions).await;
+        assert_eq!(
+            costs[0].as_ref().unwrap().value,
+            U256::from(ARBITRUM_GAS_USED * ARBITRUM_GAS_PRICE)
+        );
+    }
+
+    #[tokio::test]
+    async fn l1_costs_are_read_from_explorer_rows() {
+        let backend = ScriptedBackend::new();
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+        let transactions = [explorer_row(B256::with_last_byte(1), 21_000, GWEI)];
+
+        let costs = client.get_transaction_costs(&transactions).await;
+        assert_eq!(costs[0].as_ref().unwrap().value, U256::from(21_000 * GWEI));
+        assert!(backend.requests().is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+//! Live block and log subscriptions for [`EvmChainClient`].
+
+use alloy::rpc::types::{Filter, Log};
+use futures::Stream;
+
+use crate::{
+    EvmChainClient,
+    error::{ClientError, Result},
+    metadata::BlockHead,
+    subscription::Subscriptions,
+};
+
+impl EvmChainClient {
+    /// Sets the WebSocket endpoints used by [`Self::subscribe_new_heads`] and
+    /// [`Self::subscribe_logs`].
+    pub fn with_subscriptions(mut self, subscriptions: Subscriptions) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Returns the chain's WebSocket subscriptions.
+    ///
+    /// Fails with [`ClientError::Unsupported`] if the chain has no WebSocket RPC URL.
+    pub fn subscriptions(&self) -> Result<&Subscriptions> {
+        self.subscriptions.as_ref().ok_or_else(|| {
+            ClientError::Unsupported(format!(
+                "No WebSocket RPC endpoint for {}",
+                self.metadata.name
+            ))
+        })
+    }
+
+    /// Streams the header of every new block, subscribing again whenever the connection drops.
+    pub fn subscribe_new_heads(&self) -> Result<impl Stream<Item = BlockHead> + Send + 'static> {
+        Ok(self.subscriptions()?.new_heads())
+    }
+
+    /// Streams the logs matching the addresses and topics of `filter` as new blocks are mined,
+    /// subscribing again whenever the connection drops.
+    pub fn subscribe_logs(
+        &self,
+        filter: Filter,
+    ) -> Result<impl Stream<Item = Log> + Send + 'static> {
+        Ok(self.subscriptions()?.logs(filter))
+    }
+}
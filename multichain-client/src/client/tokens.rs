@@ -0,0 +1,266 @@
+//! On-chain ERC-20 metadata discovery for [`EvmChainClient`].
+
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, Bytes},
+    sol,
+    sol_types::SolCall,
+};
+use futures::try_join;
+use tracing::info;
+
+use crate::{
+    EvmChainClient,
+    error::{ClientError, Result},
+    metadata::TokenMetadata,
+};
+
+/// Maximum number of tokens tracked per chain.
+///
+/// Every balance lookup reads all tracked tokens, so the list cannot grow without bound through
+/// [`EvmChainClient::add_token_by_address`].
+const MAX_TRACKED_TOKENS: usize = 500;
+
+sol! {
+    function name() external view returns (string);
+    function symbol() external view returns (string);
+    function decimals() external view returns (uint8);
+}
+
+impl EvmChainClient {
+    /// Starts tracking the ERC-20 token deployed at `address`.
+    ///
+    /// Reads `name()`, `symbol()` and `decimals()` on-chain, accepting tokens such as MKR that
+    /// return `bytes32` instead of `string` for their name and symbol. Returns the token metadata,
+    /// or [`ClientError::InvalidToken`] if the address is not an ERC-20 contract.
+    ///
+    /// Also returns whether the token was newly added: tokens that are already tracked are
+    /// returned without being added twice. Fails with [`ClientError::LimitExceeded`] once the chain
+    /// tracks 500 tokens.
+    pub async fn add_token_by_address(&self, address: Address) -> Result<(TokenMetadata, bool)> {
+        let tokens = self.tokens();
+        if let Some(token) = tokens.iter().find(|t| t.address == address) {
+            return Ok((token.clone(), false));
+        }
+        if tokens.len() >= MAX_TRACKED_TOKENS {
+            return Err(self.too_many_tokens());
+        }
+
+        let token = self.get_token_metadata(address).await?;
+        info!(
+            "Tracking {} ({}) on {}",
+            token.symbol, token.address, self.metadata.name
+        );
+
+        let mut tokens = self.tokens.write().expect("token list lock poisoned");
+        // another request may have added the token while we were reading it
+        if let Some(existing) = tokens.iter().find(|t| t.address == address) {
+            return Ok((existing.clone(), false));
+        }
+        if tokens.len() >= MAX_TRACKED_TOKENS {
+            return Err(self.too_many_tokens());
+        }
+        *tokens = tokens.iter().cloned().chain([token.clone()]).collect();
+        Ok((token, true))
+    }
+
+    fn too_many_tokens(&self) -> ClientError {
+        ClientError::LimitExceeded(format!(
+            "{} already tracks {MAX_TRACKED_TOKENS} tokens",
+            self.metadata.name
+        ))
+    }
+
+    /// Reads ERC-20 metadata for the contract at `address` without tracking it.
+    pub async fn get_token_metadata(&self, address: Address) -> Result<TokenMetadata> {
+        let code = self
+            .rpc_client
+            .request::<_, Bytes>("eth_getCode", (address, BlockId::latest()))
+            .await?;
+        if code.is_empty() {
+            return Err(ClientError::InvalidToken(format!(
+                "{address} is not a contract on {}",
+                self.metadata.name
+            )));
+        }
+
+        let (name, symbol, decimals) = try_join!(
+            self.call_token_view(address, nameCall {}.abi_encode()),
+            self.call_token_view(address, symbolCall {}.abi_encode()),
+            self.call_token_view(address, decimalsCall {}.abi_encode()),
+        )?;
+
+        let not_erc20 = |what: &str| {
+            ClientError::InvalidToken(format!("{address} does not implement ERC-20 {what}"))
+        };
+        let decimals = decimals
+            .and_then(|data| decimalsCall::abi_decode_returns(&data).ok())
+            .ok_or_else(|| not_erc20("decimals()"))?;
+        let symbol = symbol
+            .and_then(|data| decode_string_or_bytes32(&data))
+            .ok_or_else(|| not_erc20("symbol()"))?;
+        // `name()` is optional in ERC-20, fall back to the symbol
+        let name = name
+            .and_then(|data| decode_string_or_bytes32(&data))
+            .unwrap_or_else(|| symbol.clone());
+
+        Ok(TokenMetadata {
+            name,
+            address,
+            symbol,
+            decimals,
+        })
+    }
+
+    /// Calls a token view function, returning `None` if the call reverts.
+    ///
+    /// Transport failures are still returned as errors, so an unreachable RPC is not mistaken for
+    /// a non-ERC-20 contract.
+    async fn call_token_view(&self, address: Address, data: Vec<u8>) -> Result<Option<Bytes>> {
+        match self
+            .eth_call::<Bytes>(address, data, BlockId::latest())
+            .await
+        {
+            Ok(data) if data.is_empty() => Ok(None),
+            Ok(data) => Ok(Some(data)),
+            Err(ClientError::Reverted(_) | ClientError::Rpc { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Decodes an ABI-encoded `string`, or a `bytes32` padded with trailing zeros as returned by
+/// older tokens like MKR. Empty values decode to `None`.
+fn decode_string_or_bytes32(data: &Bytes) -> Option<String> {
+    // an all-zero `bytes32` also decodes as an empty `string`
+    if let Ok(value) = nameCall::abi_decode_returns(data) {
+        return (!value.is_empty()).then_some(value);
+    }
+
+    if data.len() != 32 {
+        return None;
+    }
+    let end = data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    let value = std::str::from_utf8(&data[..end]).ok()?;
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, B256, Bytes, address},
+        sol_types::SolCall,
+    };
+    use serde_json::Value;
+
+    use super::{MAX_TRACKED_TOKENS, decimalsCall, decode_string_or_bytes32, nameCall, symbolCall};
+    use crate::{
+        ClientError, ScriptedBackend, ScriptedResponse,
+        test_utils::{chain_metadata, eth_call_params, scripted_client, token},
+    };
+
+    const MKR: Address = address!("0x9f8F72aA9304c8B593d555F12eF6589cC3A579A2");
+
+    fn bytes32(value: &str) -> Bytes {
+        let mut word = B256::ZERO;
+        word[..value.len()].copy_from_slice(value.as_bytes());
+        word.into()
+    }
+
+    /// Scripts a contract answering `name()` and `symbol()` with `bytes32` like MKR, and
+    /// `decimals()` with `decimals` if set.
+    fn scripted_token(decimals: Option<u8>) -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on("eth_getCode", ScriptedResponse::result("0x6080"));
+        backend.on_request("eth_call", move |params: &Value| {
+            let (_, data) = eth_call_params(params);
+            match data[..4].try_into().unwrap() {
+                nameCall::SELECTOR => ScriptedResponse::result(bytes32("Maker")),
+                symbolCall::SELECTOR => ScriptedResponse::result(bytes32("MKR")),
+                decimalsCall::SELECTOR => match decimals {
+                    Some(decimals) => ScriptedResponse::result(Bytes::from(
+                        decimalsCall::abi_encode_returns(&decimals),
+                    )),
+                    None => ScriptedResponse::error(3, "execution reverted"),
+                },
+                _ => ScriptedResponse::error(3, "execution reverted"),
+            }
+        });
+        backend
+    }
+
+    #[test]
+    fn decodes_strings_and_bytes32() {
+        let string = Bytes::from(nameCall::abi_encode_returns(&"Dai Stablecoin".to_string()));
+        assert_eq!(
+            decode_string_or_bytes32(&string).as_deref(),
+            Some("Dai Stablecoin")
+        );
+        assert_eq!(
+            decode_string_or_bytes32(&bytes32("MKR")).as_deref(),
+            Some("MKR")
+        );
+
+        assert_eq!(decode_string_or_bytes32(&bytes32("")), None);
+        assert_eq!(decode_string_or_bytes32(&Bytes::from([0xff; 32])), None);
+        assert_eq!(decode_string_or_bytes32(&Bytes::from_static(b"MKR")), None);
+    }
+
+    #[tokio::test]
+    async fn reads_bytes32_metadata() {
+        let backend = scripted_token(Some(18));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let (token, added) = client.add_token_by_address(MKR).await.unwrap();
+        assert!(added);
+        assert_eq!(token.name, "Maker");
+        assert_eq!(token.symbol, "MKR");
+        assert_eq!(token.decimals, 18);
+        assert_eq!(client.tokens().len(), 1);
+
+        let (_, added) = client.add_token_by_address(MKR).await.unwrap();
+        assert!(!added);
+        assert_eq!(client.tokens().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_contracts_that_are_not_erc20() {
+        let backend = scripted_token(None);
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client.add_token_by_address(MKR).await.unwrap_err();
+        assert!(
+            matches!(err, ClientError::InvalidToken(message) if message.contains("decimals()"))
+        );
+        assert!(client.tokens().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_accounts_without_code() {
+        let backend = scripted_token(Some(18));
+        backend.on("eth_getCode", ScriptedResponse::result("0x"));
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client.add_token_by_address(MKR).await.unwrap_err();
+        assert!(matches!(err, ClientError::InvalidToken(_)));
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn stops_tracking_new_tokens_at_the_limit() {
+        let backend = scripted_token(Some(18));
+        let tracked: Vec<_> = (0..MAX_TRACKED_TOKENS)
+            .map(|i| token("TKN", Address::left_padding_from(&i.to_be_bytes())))
+            .collect();
+        let first = tracked[0].address;
+        let client = scripted_client(chain_metadata("test", 1), &backend, tracked);
+
+        let err = client.add_token_by_address(MKR).await.unwrap_err();
+        assert!(matches!(err, ClientError::LimitExceeded(_)));
+        // already tracked tokens are still returned
+        let (_, added) = client.add_token_by_address(first).await.unwrap();
+        assert!(!added);
+        assert!(backend.requests().is_empty());
+        assert_eq!(client.tokens().len(), MAX_TRACKED_TOKENS);
+    }
+}
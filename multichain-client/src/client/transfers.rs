@@ -0,0 +1,197 @@
+//! ERC-20 transfer history for [`EvmChainClient`], read from the block explorer.
+
+use alloy::primitives::{Address, utils::format_units};
+use foundry_block_explorers::account::ERC20TokenTransferEvent;
+
+use super::EvmChainClient;
+use crate::{
+    error::Result,
+    metadata::{TokenMetadata, TokenTransfer},
+};
+
+impl EvmChainClient {
+    /// Fetches ERC-20 transfers to or from the given address from the chain's transaction source
+    /// (the explorer's `tokentx` action), newest first.
+    ///
+    /// Pagination works like [`Self::get_transactions`]: returns `(transfers, has_more)`, where
+    /// `has_more` is `true` if another page of results is available.
+    ///
+    /// Amounts are formatted with the decimals of the matching tracked token when there is one,
+    /// falling back to the decimals reported by the explorer.
+    pub async fn get_token_transfers(
+        &self,
+        address: Address,
+        page: u64,
+        offset: u64,
+    ) -> Result<(Vec<TokenTransfer>, bool)> {
+        let (events, has_more) = self
+            .explorer_page(page, offset, |params| {
+                self.transaction_source.token_transfers(address, params)
+            })
+            .await?;
+
+        let tokens = self.tokens();
+        let transfers = events
+            .into_iter()
+            .map(|event| {
+                let token = tokens
+                    .iter()
+                    .find(|token| token.address == event.contract_address)
+                    .cloned()
+                    .unwrap_or_else(|| explorer_token_metadata(&event));
+                let amount = format_units(event.value, token.decimals)
+                    .unwrap_or_else(|_| event.value.to_string());
+
+                TokenTransfer {
+                    hash: event.hash,
+                    block_number: event.block_number.as_number().map(|n| n.to()),
+                    timestamp: event.time_stamp.parse().unwrap_or_default(),
+                    from: event.from,
+                    to: event.to,
+                    token,
+                    value: event.value,
+                    amount,
+                }
+            })
+            .collect();
+
+        Ok((transfers, has_more))
+    }
+}
+
+/// Builds token metadata from the details the explorer attaches to a transfer event.
+fn explorer_token_metadata(event: &ERC20TokenTransferEvent) -> TokenMetadata {
+    TokenMetadata {
+        name: event.token_name.clone(),
+        address: event.contract_address,
+        symbol: event.token_symbol.clone(),
+        decimals: event.token_decimal.parse().unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use alloy::{
+        json_abi::JsonAbi,
+        primitives::{Address, U256, address},
+    };
+    use foundry_block_explorers::account::{
+        ERC20TokenTransferEvent, InternalTransaction, NormalTransaction, TxListParams,
+    };
+    use futures::future::BoxFuture;
+    use serde_json::json;
+
+    use crate::{
+        ClientError, EvmChainClient, ScriptedBackend, TokenMetadata,
+        error::Result,
+        history::TransactionSource,
+        test_utils::{chain_metadata, token},
+    };
+
+    const OWNER: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const UNTRACKED: Address = address!("0x00000000000000000000000000000000000000cc");
+
+    /// A transaction source listing one transfer of [`USDC`] and one of [`UNTRACKED`], both
+    /// reported with 8 decimals by the explorer.
+    #[derive(Debug)]
+    struct ScriptedTransfers;
+
+    fn transfer(contract: Address, symbol: &str) -> ERC20TokenTransferEvent {
+        serde_json::from_value(json!({
+            "blockNumber": "19000000",
+            "timeStamp": "1705000000",
+            "hash": "0x3d6f0e5a1b2c4d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9a0b1c2d3e4f50",
+            "nonce": "42",
+            "blockHash": "0x8c1f2e3d4c5b6a79881726354453627180f9e8d7c6b5a4938271605f4e3d2c1b",
+            "from": OWNER,
+            "contractAddress": contract,
+            "to": "0x00000000000000000000000000000000000000bb",
+            "value": "1500000",
+            "tokenName": format!("Explorer {symbol}"),
+            "tokenSymbol": symbol,
+            "tokenDecimal": "8",
+            "transactionIndex": "7",
+            "gas": "52000",
+            "gasPrice": "23000000000",
+            "gasUsed": "41000",
+            "cumulativeGasUsed": "1234567",
+            "input": "deprecated",
+            "confirmations": "100"
+        }))
+        .unwrap()
+    }
+
+    impl TransactionSource for ScriptedTransfers {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn transactions(
+            &self,
+            _address: Address,
+            _params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+            Box::pin(async { Err(ClientError::NoTransactions) })
+        }
+
+        fn internal_transactions(
+            &self,
+            _address: Address,
+            _params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+            Box::pin(async { Err(ClientError::NoTransactions) })
+        }
+
+        fn token_transfers(
+            &self,
+            _address: Address,
+            params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+            Box::pin(async move {
+                if params.page > 1 {
+                    return Err(ClientError::NoTransactions);
+                }
+                Ok(vec![transfer(USDC, "USDC"), transfer(UNTRACKED, "UNK")])
+            })
+        }
+
+        fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+            Box::pin(async move { Err(ClientError::NotFound(format!("ABI of {address}"))) })
+        }
+    }
+
+    #[tokio::test]
+    async fn amounts_use_tracked_decimals_then_the_explorer() {
+        let usdc = TokenMetadata {
+            decimals: 6,
+            ..token("USDC", USDC)
+        };
+        let client = EvmChainClient::with_backend(
+            chain_metadata("test", 1),
+            ScriptedBackend::new(),
+            Arc::new(ScriptedTransfers),
+            vec![usdc],
+        );
+
+        let (transfers, has_more) = client.get_token_transfers(OWNER, 1, 10).await.unwrap();
+        assert!(!has_more);
+        assert_eq!(transfers.len(), 2);
+
+        // tracked tokens keep their own metadata
+        assert_eq!(transfers[0].token.name, "USDC");
+        assert_eq!(transfers[0].token.decimals, 6);
+        assert_eq!(transfers[0].amount, "1.500000");
+
+        // other tokens are described by the explorer
+        assert_eq!(transfers[1].token.address, UNTRACKED);
+        assert_eq!(transfers[1].token.name, "Explorer UNK");
+        assert_eq!(transfers[1].token.decimals, 8);
+        assert_eq!(transfers[1].amount, "0.01500000");
+        assert_eq!(transfers[1].value, U256::from(1_500_000));
+        assert_eq!(transfers[1].block_number, Some(19_000_000));
+        assert_eq!(transfers[1].timestamp, 1_705_000_000);
+    }
+}
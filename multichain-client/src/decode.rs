@@ -0,0 +1,564 @@
+//! Function selector and event topic lookup, with calldata and log decoding.
+//!
+//! A [`SignatureDatabase`] maps 4-byte selectors to candidate function signatures loaded from a
+//! 4byte-style file, and event topics to a built-in list of well-known events. Contract ABIs add
+//! functions and events that only apply to that contract. It decodes calldata and logs into
+//! named, typed arguments.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use alloy::{
+    dyn_abi::{DynSolValue, EventExt, JsonAbiExt},
+    hex,
+    json_abi::{Event, Function, JsonAbi, Param},
+    primitives::{Address, B256, LogData, Selector},
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::error::{ClientError, Result};
+
+/// Calldata decoded against a known function signature.
+#[derive(Clone, Debug, Serialize)]
+pub struct DecodedCall {
+    /// The 4-byte function selector
+    pub selector: Selector,
+
+    /// Function name (e.g., "transfer")
+    pub name: String,
+
+    /// Canonical signature (e.g., "transfer(address,uint256)")
+    pub signature: String,
+
+    /// Decoded arguments, in declaration order
+    pub args: Vec<DecodedArg>,
+}
+
+/// A log decoded against a known event.
+#[derive(Clone, Debug, Serialize)]
+pub struct DecodedLog {
+    /// The event topic (`topic0`)
+    pub topic: B256,
+
+    /// Event name (e.g., "Transfer")
+    pub name: String,
+
+    /// Canonical signature (e.g., "Transfer(address,address,uint256)")
+    pub signature: String,
+
+    /// Decoded arguments, in declaration order
+    ///
+    /// Indexed arguments of dynamic types (strings, bytes and arrays) are only logged as their
+    /// hash, so their value is that hash.
+    pub args: Vec<DecodedArg>,
+}
+
+/// A single decoded function or event argument.
+#[derive(Clone, Debug, Serialize)]
+pub struct DecodedArg {
+    /// Parameter name, or an empty string when the signature does not name it
+    pub name: String,
+
+    /// Canonical Solidity type (e.g., "uint256" or "(address,bool)[]")
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// Decoded value: addresses are checksummed, integers are decimal strings, bytes are hex,
+    /// and tuples with named components are objects
+    pub value: Value,
+}
+
+/// Well-known events, decoded even without a contract ABI.
+const KNOWN_EVENTS: &[&str] = &[
+    // ERC-20
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    // ERC-721
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    // Uniswap V2
+    "event Swap(address indexed sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address indexed to)",
+    // Uniswap V3
+    "event Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 sqrtPriceX96, uint128 liquidity, int24 tick)",
+];
+
+/// A database of function signatures keyed by selector, and of events keyed by topic.
+///
+/// Several signatures can share a selector. Functions and events added from the ABI of a
+/// contract are only used for calls to and logs from that contract, where they take precedence
+/// over plain signatures and well-known events, since they carry parameter names and are known to
+/// belong to it.
+#[derive(Debug)]
+pub struct SignatureDatabase {
+    signatures: RwLock<Signatures>,
+}
+
+#[derive(Debug, Default)]
+struct Signatures {
+    functions: HashMap<Selector, Vec<Function>>,
+    events: HashMap<B256, Vec<Event>>,
+    /// Functions and events of contract ABIs, keyed by chain ID and contract address
+    contracts: HashMap<(u64, Address), Signatures>,
+}
+
+impl SignatureDatabase {
+    /// Constructs a `SignatureDatabase` that only knows the well-known ERC-20, ERC-721 and
+    /// Uniswap events.
+    pub fn new() -> Self {
+        let mut signatures = Signatures::default();
+        for event in KNOWN_EVENTS {
+            let event = Event::parse(event).expect("well-known events are valid");
+            signatures
+                .events
+                .entry(event.selector())
+                .or_default()
+                .push(event);
+        }
+        Self {
+            signatures: RwLock::new(signatures),
+        }
+    }
+
+    /// Loads a 4byte-style signature file.
+    ///
+    /// The file should be formatted as an object of selectors to signatures:
+    /// `{ "0xa9059cbb": ["transfer(address,uint256)"] }`
+    ///
+    /// Signatures that cannot be parsed or do not hash to their selector are skipped.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| ClientError::Config(format!("Failed to read {}: {e}", path.display())))?;
+        let entries: HashMap<Selector, Vec<String>> = serde_json::from_str(&data).map_err(|e| {
+            ClientError::Config(format!("Invalid signature file {}: {e}", path.display()))
+        })?;
+
+        let db = Self::new();
+        for (selector, signatures) in entries {
+            for signature in signatures {
+                match db.add_signature(&signature) {
+                    Ok(actual) if actual == selector => {}
+                    Ok(actual) => warn!(
+                        "Signature {signature} has selector {actual}, not {selector}; skipping"
+                    ),
+                    Err(e) => warn!("{e}; skipping"),
+                }
+            }
+        }
+        Ok(db)
+    }
+
+    /// Adds a signature such as `transfer(address,uint256)` and returns its selector.
+    ///
+    /// Fails with [`ClientError::Config`] if the signature cannot be parsed.
+    pub fn add_signature(&self, signature: &str) -> Result<Selector> {
+        let function = Function::parse(signature)
+            .map_err(|e| ClientError::Config(format!("Invalid signature {signature}: {e}")))?;
+        let selector = function.selector();
+
+        let mut signatures = self.write();
+        let candidates = signatures.functions.entry(selector).or_default();
+        if candidates
+            .iter()
+            .all(|f| f.signature() != function.signature())
+        {
+            candidates.push(function);
+        }
+        Ok(selector)
+    }
+
+    /// Adds every function and event of the ABI of `address` on the chain with ID `chain_id`.
+    ///
+    /// They are used for calls to and logs from that contract only, ahead of signatures with the
+    /// same selector or topic, including those of ABIs added to it before.
+    pub fn add_abi(&self, chain_id: u64, address: Address, abi: &JsonAbi) {
+        let mut signatures = self.write();
+        let signatures = signatures.contracts.entry((chain_id, address)).or_default();
+        for function in abi.functions() {
+            let candidates = signatures.functions.entry(function.selector()).or_default();
+            candidates.retain(|f| f.signature() != function.signature());
+            candidates.insert(0, function.clone());
+        }
+        for event in abi.events().filter(|event| !event.anonymous) {
+            let candidates = signatures.events.entry(event.selector()).or_default();
+            candidates.retain(|e| e != event);
+            candidates.insert(0, event.clone());
+        }
+    }
+
+    /// Returns `true` if an ABI was added for `address` on the chain with ID `chain_id`.
+    pub fn has_abi(&self, chain_id: u64, address: Address) -> bool {
+        self.read().contracts.contains_key(&(chain_id, address))
+    }
+
+    /// Returns the number of function selectors in the database, outside of contract ABIs.
+    pub fn len(&self) -> usize {
+        self.read().functions.len()
+    }
+
+    /// Returns `true` if the database has no function signatures.
+    pub fn is_empty(&self) -> bool {
+        self.read().functions.is_empty()
+    }
+
+    /// Returns the candidate functions for `selector` in calls to `to` on the chain with ID
+    /// `chain_id`, in order of precedence.
+    pub fn lookup(&self, chain_id: u64, to: Option<Address>, selector: Selector) -> Vec<Function> {
+        let signatures = self.read();
+        let contract = to.and_then(|to| signatures.contracts.get(&(chain_id, to)));
+        contract
+            .and_then(|contract| contract.functions.get(&selector))
+            .into_iter()
+            .chain(signatures.functions.get(&selector))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the candidate events for `topic` in logs from `address` on the chain with ID
+    /// `chain_id`, in order of precedence.
+    pub fn lookup_event(&self, chain_id: u64, address: Address, topic: B256) -> Vec<Event> {
+        let signatures = self.read();
+        let contract = signatures.contracts.get(&(chain_id, address));
+        contract
+            .and_then(|contract| contract.events.get(&topic))
+            .into_iter()
+            .chain(signatures.events.get(&topic))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Decodes a log emitted by `address` on the chain with ID `chain_id` against the known
+    /// events for its first topic.
+    ///
+    /// Events sharing a topic are told apart by their number of indexed arguments (e.g. ERC-20
+    /// and ERC-721 `Transfer`). Returns `None` for anonymous and unknown events.
+    pub fn decode_log(&self, chain_id: u64, address: Address, log: &LogData) -> Option<DecodedLog> {
+        let topic = *log.topics().first()?;
+        self.lookup_event(chain_id, address, topic)
+            .iter()
+            .filter(|event| event.num_topics() == log.topics().len())
+            .find_map(|event| {
+                let decoded = event.decode_log(log).ok()?;
+                Some(decoded_log(event, decoded.indexed, decoded.body))
+            })
+    }
+
+    /// Decodes the input of a transaction to `to` on the chain with ID `chain_id` against the
+    /// known signatures for its selector.
+    ///
+    /// A candidate whose re-encoded arguments match the calldata exactly is preferred; otherwise
+    /// the first candidate that decodes at all is used, which tolerates trailing data appended
+    /// by some wallets. Returns `None` for plain transfers and unknown selectors.
+    pub fn decode(&self, chain_id: u64, to: Option<Address>, input: &[u8]) -> Option<DecodedCall> {
+        let selector = Selector::try_from(input.get(..4)?).ok()?;
+        let candidates = self.lookup(chain_id, to, selector);
+
+        let mut fallback = None;
+        for function in &candidates {
+            let Ok(values) = function.abi_decode_input(&input[4..]) else {
+                continue;
+            };
+            let exact = function
+                .abi_encode_input(&values)
+                .is_ok_and(|encoded| encoded == input);
+            if exact {
+                return Some(decoded_call(function, values));
+            }
+            fallback.get_or_insert((function, values));
+        }
+        fallback.map(|(function, values)| decoded_call(function, values))
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Signatures> {
+        self.signatures
+            .read()
+            .expect("signature database lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Signatures> {
+        self.signatures
+            .write()
+            .expect("signature database lock poisoned")
+    }
+}
+
+impl Default for SignatureDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes calldata against a single function from a contract ABI.
+///
+/// Returns `None` if the selector does not match or the arguments cannot be decoded.
+pub fn decode_calldata(function: &Function, input: &[u8]) -> Option<DecodedCall> {
+    if input.get(..4)? != function.selector().as_slice() {
+        return None;
+    }
+    let values = function.abi_decode_input(&input[4..]).ok()?;
+    Some(decoded_call(function, values))
+}
+
+fn decoded_call(function: &Function, values: Vec<DynSolValue>) -> DecodedCall {
+    let args = function
+        .inputs
+        .iter()
+        .zip(&values)
+        .map(|(param, value)| DecodedArg {
+            name: param.name.clone(),
+            ty: param.selector_type().into_owned(),
+            value: to_json(value, &param.components),
+        })
+        .collect();
+
+    DecodedCall {
+        selector: function.selector(),
+        name: function.name.clone(),
+        signature: function.signature(),
+        args,
+    }
+}
+
+fn decoded_log(event: &Event, indexed: Vec<DynSolValue>, body: Vec<DynSolValue>) -> DecodedLog {
+    let mut indexed = indexed.into_iter();
+    let mut body = body.into_iter();
+    let args = event
+        .inputs
+        .iter()
+        .filter_map(|param| {
+            let value = if param.indexed {
+                indexed.next()
+            } else {
+                body.next()
+            }?;
+            Some(DecodedArg {
+                name: param.name.clone(),
+                ty: param.selector_type().into_owned(),
+                value: to_json(&value, &param.components),
+            })
+        })
+        .collect();
+
+    DecodedLog {
+        topic: event.selector(),
+        name: event.name.clone(),
+        signature: event.signature(),
+        args,
+    }
+}
+
+/// Converts a decoded value to JSON; `components` describes the fields of tuples, or of the
+/// elements of tuple arrays.
+fn to_json(value: &DynSolValue, components: &[Param]) -> Value {
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(i, _) => Value::String(i.to_string()),
+        DynSolValue::Uint(u, _) => Value::String(u.to_string()),
+        DynSolValue::FixedBytes(word, size) => Value::String(hex::encode_prefixed(&word[..*size])),
+        DynSolValue::Address(address) => Value::String(address.to_checksum(None)),
+        DynSolValue::Function(function) => Value::String(hex::encode_prefixed(function)),
+        DynSolValue::Bytes(bytes) => Value::String(hex::encode_prefixed(bytes)),
+        DynSolValue::String(s) => Value::String(s.clone()),
+        DynSolValue::Array(items) | DynSolValue::FixedArray(items) => {
+            items.iter().map(|item| to_json(item, components)).collect()
+        }
+        DynSolValue::Tuple(fields) => {
+            let named =
+                components.len() == fields.len() && components.iter().all(|c| !c.name.is_empty());
+            if named {
+                let object: Map<_, _> = components
+                    .iter()
+                    .zip(fields)
+                    .map(|(c, field)| (c.name.clone(), to_json(field, &c.components)))
+                    .collect();
+                Value::Object(object)
+            } else {
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let inner = components.get(i).map_or(&[][..], |c| &c.components);
+                        to_json(field, inner)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        dyn_abi::{DynSolValue, JsonAbiExt},
+        json_abi::{Function, JsonAbi},
+        primitives::{Address, I256, LogData, U256, address},
+    };
+    use serde_json::json;
+
+    use super::{SignatureDatabase, decode_calldata};
+
+    const TOKEN: Address = address!("0x00000000000000000000000000000000000000aa");
+    const OTHER: Address = address!("0x00000000000000000000000000000000000000bb");
+
+    #[test]
+    fn abis_only_apply_to_their_contract() {
+        let signatures = SignatureDatabase::new();
+        let abi = JsonAbi::parse([
+            "function mint(uint256 amount)",
+            "event Minted(uint256 amount)",
+        ])
+        .unwrap();
+        signatures.add_abi(1, TOKEN, &abi);
+        assert!(signatures.has_abi(1, TOKEN));
+        assert!(!signatures.has_abi(10, TOKEN));
+
+        let mint = &abi.function("mint").unwrap()[0];
+        let input = mint
+            .abi_encode_input(&[DynSolValue::Uint(U256::from(5), 256)])
+            .unwrap();
+        let decoded = signatures.decode(1, Some(TOKEN), &input).unwrap();
+        assert_eq!(decoded.signature, "mint(uint256)");
+        assert_eq!(decoded.args[0].name, "amount");
+        assert!(signatures.decode(1, Some(OTHER), &input).is_none());
+        assert!(signatures.decode(10, Some(TOKEN), &input).is_none());
+        assert!(signatures.decode(1, None, &input).is_none());
+
+        let minted = abi.event("Minted").unwrap()[0].selector();
+        let log = LogData::new_unchecked(vec![minted], U256::from(5).to_be_bytes_vec().into());
+        assert!(signatures.decode_log(1, TOKEN, &log).is_some());
+        assert!(signatures.decode_log(1, OTHER, &log).is_none());
+    }
+
+    /// Encodes a `transfer(address,uint256)` call of 5 to [`OTHER`].
+    fn transfer_input() -> Vec<u8> {
+        Function::parse("transfer(address,uint256)")
+            .unwrap()
+            .abi_encode_input(&[
+                DynSolValue::Address(OTHER),
+                DynSolValue::Uint(U256::from(5), 256),
+            ])
+            .unwrap()
+    }
+
+    #[test]
+    fn prefers_candidates_matching_the_calldata_exactly() {
+        let signatures = SignatureDatabase::new();
+        // a known collision with `transfer(address,uint256)`, added first
+        let collision = signatures
+            .add_signature("many_msg_babbage(bytes1)")
+            .unwrap();
+        let selector = signatures
+            .add_signature("transfer(address,uint256)")
+            .unwrap();
+        assert_eq!(collision, selector);
+        // the collision decodes the calldata too, but ignores most of it
+        let input = transfer_input();
+        assert!(
+            Function::parse("many_msg_babbage(bytes1)")
+                .unwrap()
+                .abi_decode_input(&input[4..])
+                .is_ok()
+        );
+
+        let decoded = signatures
+            .decode(1, Some(TOKEN), &transfer_input())
+            .unwrap();
+        assert_eq!(decoded.signature, "transfer(address,uint256)");
+    }
+
+    #[test]
+    fn falls_back_to_candidates_decoding_with_trailing_data() {
+        let signatures = SignatureDatabase::new();
+        signatures
+            .add_signature("transfer(address,uint256)")
+            .unwrap();
+
+        let mut input = transfer_input();
+        input.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        let decoded = signatures.decode(1, Some(TOKEN), &input).unwrap();
+        assert_eq!(decoded.signature, "transfer(address,uint256)");
+        assert_eq!(decoded.args[1].value, json!("5"));
+
+        assert!(signatures.decode(1, Some(TOKEN), &input[..3]).is_none());
+        assert!(signatures.decode(1, Some(TOKEN), &[0, 0, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn renders_tuples_and_arrays() {
+        // signatures cannot name tuple components, so this is parsed from a JSON ABI
+        let function: Function = serde_json::from_value(json!({
+            "type": "function",
+            "name": "settle",
+            "inputs": [
+                {
+                    "name": "orders",
+                    "type": "tuple[]",
+                    "components": [
+                        { "name": "to", "type": "address" },
+                        { "name": "amount", "type": "uint256" },
+                    ],
+                },
+                {
+                    "name": "flags",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "", "type": "bool" },
+                        { "name": "", "type": "bytes4" },
+                    ],
+                },
+                { "name": "delta", "type": "int8" },
+                { "name": "data", "type": "bytes" },
+            ],
+            "outputs": [],
+            "stateMutability": "nonpayable",
+        }))
+        .unwrap();
+        let order = |amount: u64| {
+            DynSolValue::Tuple(vec![
+                DynSolValue::Address(OTHER),
+                DynSolValue::Uint(U256::from(amount), 256),
+            ])
+        };
+        let input = function
+            .abi_encode_input(&[
+                DynSolValue::Array(vec![order(1), order(2)]),
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Bool(true),
+                    DynSolValue::FixedBytes([0xab; 32].into(), 4),
+                ]),
+                DynSolValue::Int(I256::try_from(-3).unwrap(), 8),
+                DynSolValue::Bytes(vec![0x01, 0x02]),
+            ])
+            .unwrap();
+
+        let decoded = decode_calldata(&function, &input).unwrap();
+        let types: Vec<_> = decoded.args.iter().map(|arg| arg.ty.as_str()).collect();
+        assert_eq!(
+            types,
+            ["(address,uint256)[]", "(bool,bytes4)", "int8", "bytes"]
+        );
+        let other = OTHER.to_checksum(None);
+        let values: Vec<_> = decoded.args.iter().map(|arg| &arg.value).collect();
+        assert_eq!(
+            values,
+            [
+                &json!([
+                    { "to": other, "amount": "1" },
+                    { "to": other, "amount": "2" },
+                ]),
+                // unnamed components are rendered as arrays
+                &json!([true, "0xabababab"]),
+                &json!("-3"),
+                &json!("0x0102"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! A failover pool of RPC endpoints for a single chain.
+//!
+//! [`EndpointPool`] is the [`ChainBackend`] used by production clients. Requests are sent to
+//! the currently active endpoint and rotate to the next one on transport errors, timeouts or
+//! HTTP 429. Endpoints that keep failing are put in a cool-down period before being tried again.
+//!
+//! Endpoints are usually HTTP or WebSocket URLs, but any [`ChainBackend`] can be pooled, which
+//! allows the failover logic to be exercised against a [`ScriptedBackend`](crate::ScriptedBackend).
+
+use std::{
+    fmt,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{
+        RpcError, TransportError, TransportErrorKind, TransportFut,
+        http::{
+            Http,
+            reqwest::{Client as HttpClient, Url},
+        },
+    },
+};
+use tracing::{debug, warn};
+
+use crate::{
+    backend::{ChainBackend, WsBackend},
+    error::{ClientError, Result},
+};
+
+/// Timeout applied to every HTTP request sent by the pool.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long an unhealthy endpoint is skipped before being tried again.
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Health score of a fresh endpoint, and the maximum score an endpoint can reach.
+const MAX_SCORE: u8 = 100;
+
+/// Score gained on every successful request.
+const SUCCESS_REWARD: u8 = 10;
+
+/// Score lost on every failed request.
+const FAILURE_PENALTY: u8 = 25;
+
+/// Endpoints at or below this score are sent to cool-down.
+const COOLDOWN_THRESHOLD: u8 = 50;
+
+/// Score an endpoint gets back when it enters cool-down, so a single failure after the
+/// cool-down expires sends it straight back.
+const COOLDOWN_SCORE: u8 = 75;
+
+/// A single RPC endpoint and its health bookkeeping.
+struct Endpoint {
+    name: String,
+    backend: Arc<dyn ChainBackend>,
+    health: Mutex<EndpointHealth>,
+}
+
+/// Health score and cool-down state of an endpoint.
+struct EndpointHealth {
+    score: u8,
+    cooldown_until: Option<Instant>,
+}
+
+impl Endpoint {
+    fn is_cooling_down(&self, now: Instant) -> bool {
+        self.health
+            .lock()
+            .expect("endpoint health lock poisoned")
+            .cooldown_until
+            .is_some_and(|until| until > now)
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health.lock().expect("endpoint health lock poisoned");
+        health.score = health.score.saturating_add(SUCCESS_REWARD).min(MAX_SCORE);
+        health.cooldown_until = None;
+    }
+
+    /// Records a failure, returning `true` if the endpoint was sent to cool-down.
+    fn record_failure(&self) -> bool {
+        let mut health = self.health.lock().expect("endpoint health lock poisoned");
+        health.score = health.score.saturating_sub(FAILURE_PENALTY);
+        if health.score <= COOLDOWN_THRESHOLD {
+            health.score = COOLDOWN_SCORE;
+            health.cooldown_until = Some(Instant::now() + COOLDOWN);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A pool of RPC endpoints for one chain, with failover and per-endpoint health tracking.
+#[derive(Clone)]
+pub struct EndpointPool {
+    chain: Arc<str>,
+    endpoints: Arc<[Endpoint]>,
+    active: Arc<AtomicUsize>,
+}
+
+impl EndpointPool {
+    /// Constructs a new `EndpointPool` for `chain` from a non-empty list of HTTP and WebSocket
+    /// endpoint URLs.
+    pub fn new(chain: &str, urls: Vec<Url>) -> Result<Self> {
+        Self::with_ws_backends(chain, urls, &[])
+    }
+
+    /// Constructs a new `EndpointPool` for `chain` from a non-empty list of HTTP and WebSocket
+    /// endpoint URLs, sending requests to WebSocket URLs through the backend in `ws_backends`
+    /// with the same URL, if any.
+    ///
+    /// This lets the pool share its WebSocket connections with [`Subscriptions`] built from
+    /// the same backends.
+    ///
+    /// [`Subscriptions`]: crate::Subscriptions
+    pub fn with_ws_backends(
+        chain: &str,
+        urls: Vec<Url>,
+        ws_backends: &[WsBackend],
+    ) -> Result<Self> {
+        let client = HttpClient::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ClientError::Config(format!("Failed to build HTTP client: {e}")))?;
+        let backends = urls
+            .into_iter()
+            .map(|url| {
+                let backend: Arc<dyn ChainBackend> = match url.scheme() {
+                    "ws" | "wss" => Arc::new(
+                        ws_backends
+                            .iter()
+                            .find(|backend| backend.url() == url.as_str())
+                            .cloned()
+                            .unwrap_or_else(|| WsBackend::new(url.as_str())),
+                    ),
+                    _ => Arc::new(Http::with_client(client.clone(), url.clone())),
+                };
+                (url.to_string(), backend)
+            })
+            .collect();
+
+        Self::with_backends(chain, backends)
+    }
+
+    /// Constructs a new `EndpointPool` for `chain` from a non-empty list of named backends.
+    ///
+    /// Names identify the endpoints in logs.
+    pub fn with_backends(
+        chain: &str,
+        backends: Vec<(String, Arc<dyn ChainBackend>)>,
+    ) -> Result<Self> {
+        if backends.is_empty() {
+            return Err(ClientError::Config(format!(
+                "No RPC endpoints for chain: {chain}"
+            )));
+        }
+
+        let endpoints = backends
+            .into_iter()
+            .map(|(name, backend)| Endpoint {
+                name,
+                backend,
+                health: Mutex::new(EndpointHealth {
+                    score: MAX_SCORE,
+                    cooldown_until: None,
+                }),
+            })
+            .collect();
+
+        Ok(Self {
+            chain: chain.into(),
+            endpoints,
+            active: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Returns the names of all endpoints in the pool, in failover order.
+    ///
+    /// For HTTP and WebSocket endpoints, the name is the URL.
+    pub fn endpoints(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().map(|endpoint| endpoint.name.as_str())
+    }
+
+    /// Returns the order in which endpoints should be tried, starting from the active one.
+    ///
+    /// Endpoints in cool-down are moved to the back, so they are only used when every other
+    /// endpoint has failed.
+    fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let len = self.endpoints.len();
+        let start = self.active.load(Ordering::Relaxed);
+
+        let (mut healthy, cooling): (Vec<_>, Vec<_>) = (0..len)
+            .map(|offset| (start + offset) % len)
+            .partition(|&idx| !self.endpoints[idx].is_cooling_down(now));
+        healthy.extend(cooling);
+        healthy
+    }
+
+    async fn send_with_failover(
+        self,
+        request: RequestPacket,
+    ) -> Result<ResponsePacket, TransportError> {
+        let mut last_err = None;
+
+        for idx in self.candidates() {
+            let endpoint = &self.endpoints[idx];
+
+            match endpoint.backend.send(request.clone()).await {
+                Err(err @ RpcError::Transport(_)) => {
+                    warn!(
+                        chain = %self.chain,
+                        endpoint = %endpoint.name,
+                        "RPC endpoint failed: {err}"
+                    );
+                    if endpoint.record_failure() {
+                        warn!(
+                            chain = %self.chain,
+                            endpoint = %endpoint.name,
+                            "RPC endpoint cooling down for {COOLDOWN:?}"
+                        );
+                    }
+                    // rotate away from the failing endpoint for subsequent requests
+                    let _ = self.active.compare_exchange(
+                        idx,
+                        (idx + 1) % self.endpoints.len(),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    );
+                    last_err = Some(err);
+                }
+                response => {
+                    endpoint.record_success();
+                    debug!(chain = %self.chain, endpoint = %endpoint.name, "RPC request served");
+                    return response;
+                }
+            }
+        }
+
+        Err(last_err
+            .unwrap_or_else(|| TransportErrorKind::custom_str("No RPC endpoints available")))
+    }
+}
+
+impl fmt::Debug for EndpointPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EndpointPool")
+            .field("chain", &self.chain)
+            .field("endpoints", &self.endpoints().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ChainBackend for EndpointPool {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        Box::pin(self.clone().send_with_failover(request))
+    }
+
+    fn fails_over(&self) -> bool {
+        self.endpoints.len() > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::EndpointPool;
+    use crate::{
+        ChainBackend, ClientError, EvmChainClient, NoTransactionSource, ScriptedBackend,
+        ScriptedResponse, retry::RetryPolicy, test_utils::chain_metadata,
+    };
+
+    fn pool(backends: &[&ScriptedBackend]) -> EndpointPool {
+        let backends = backends
+            .iter()
+            .enumerate()
+            .map(|(i, &backend)| {
+                let backend: Arc<dyn ChainBackend> = Arc::new(backend.clone());
+                (format!("endpoint-{i}"), backend)
+            })
+            .collect();
+        EndpointPool::with_backends("test", backends).unwrap()
+    }
+
+    /// Constructs a client sending its requests through `pool`.
+    fn pooled_client(pool: EndpointPool) -> EvmChainClient {
+        EvmChainClient::with_backend(
+            chain_metadata("test", 1),
+            pool,
+            Arc::new(NoTransactionSource::new("test")),
+            vec![],
+        )
+    }
+
+    fn block_number(number: u64) -> ScriptedResponse {
+        ScriptedResponse::result(format!("{number:#x}"))
+    }
+
+    fn unreachable() -> ScriptedResponse {
+        ScriptedResponse::TransportError("connection refused".to_string())
+    }
+
+    #[tokio::test]
+    async fn fails_over_and_stays_on_the_healthy_endpoint() {
+        let down = ScriptedBackend::new();
+        down.on("eth_blockNumber", unreachable());
+        let up = ScriptedBackend::new();
+        up.on("eth_blockNumber", block_number(7));
+        let client = pooled_client(pool(&[&down, &up]));
+
+        assert_eq!(client.get_block_number().await.unwrap(), 7);
+        assert_eq!(client.get_block_number().await.unwrap(), 7);
+        assert_eq!(down.requests().len(), 1);
+        assert_eq!(up.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn json_rpc_errors_do_not_fail_over() {
+        let first = ScriptedBackend::new();
+        first.on(
+            "eth_blockNumber",
+            ScriptedResponse::error(-32000, "internal error"),
+        );
+        let second = ScriptedBackend::new();
+        second.on("eth_blockNumber", block_number(7));
+        let client = pooled_client(pool(&[&first, &second]));
+
+        let err = client.get_block_number().await.unwrap_err();
+        assert!(matches!(err, ClientError::Rpc { code: -32000, .. }));
+        assert!(second.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_when_every_endpoint_is_down() {
+        let first = ScriptedBackend::new();
+        first.on("eth_blockNumber", unreachable());
+        let second = ScriptedBackend::new();
+        second.on("eth_blockNumber", unreachable());
+        let client = pooled_client(pool(&[&first, &second]));
+
+        let err = client.get_block_number().await.unwrap_err();
+        assert!(matches!(err, ClientError::Transport(_)));
+        assert_eq!(first.requests().len(), 1);
+        assert_eq!(second.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn transport_errors_are_not_retried_on_top_of_failover() {
+        let first = ScriptedBackend::new();
+        first.on("eth_blockNumber", unreachable());
+        let second = ScriptedBackend::new();
+        second.on("eth_blockNumber", unreachable());
+        let mut metadata = chain_metadata("test", 1);
+        metadata.retry = Some(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_ms: 0,
+            max_backoff_ms: 0,
+        });
+        let client = EvmChainClient::with_backend(
+            metadata,
+            pool(&[&first, &second]),
+            Arc::new(NoTransactionSource::new("test")),
+            vec![],
+        );
+
+        assert!(client.get_block_number().await.is_err());
+        assert_eq!(first.requests().len(), 1);
+        assert_eq!(second.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn endpoints_in_cooldown_are_tried_last() {
+        let flaky = ScriptedBackend::new();
+        flaky.on("eth_blockNumber", block_number(7));
+        let fallback = ScriptedBackend::new();
+        fallback.on("eth_blockNumber", block_number(7));
+        let pool = pool(&[&flaky, &fallback]);
+
+        assert!(!pool.endpoints[0].record_failure());
+        assert!(pool.endpoints[0].record_failure());
+        assert_eq!(pool.candidates(), [1, 0]);
+
+        // `flaky` is still the active endpoint, but is skipped while cooling down
+        let client = pooled_client(pool);
+        assert_eq!(client.get_block_number().await.unwrap(), 7);
+        assert!(flaky.requests().is_empty());
+        assert_eq!(fallback.requests().len(), 1);
+    }
+}
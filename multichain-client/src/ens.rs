@@ -0,0 +1,128 @@
+//! ENS name normalization and hashing.
+//!
+//! Normalization is limited to the ASCII subset of ENSIP-15: names with emoji or non-Latin
+//! characters are rejected rather than normalized, so they can neither be resolved nor reported
+//! as primary names.
+//!
+//! Resolution itself is done by [`EvmChainClient::resolve_name`] and
+//! [`EvmChainClient::lookup_address`] through `eth_call`s to the ENS registry and resolvers.
+//!
+//! [`EvmChainClient::resolve_name`]: crate::EvmChainClient::resolve_name
+//! [`EvmChainClient::lookup_address`]: crate::EvmChainClient::lookup_address
+
+use alloy::primitives::{Address, B256, address, keccak256};
+
+use crate::error::{ClientError, Result};
+
+/// Address of the ENS registry on Ethereum mainnet, used when a chain with ID 1 does not
+/// configure `ensRegistry`.
+pub const MAINNET_ENS_REGISTRY: Address = address!("0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+/// Normalizes an ENS name for hashing.
+///
+/// This is not a full ENSIP-15 implementation: only ASCII names are supported, for which
+/// normalization amounts to trimming and lowercasing the name. Fails with [`ClientError::InvalidName`] for names with non-ASCII
+/// characters, which would need the full ENSIP-15 tables to normalize, and for names ENSIP-15
+/// rejects: empty labels, characters other than letters, digits, `-` and `_`, underscores
+/// anywhere but at the start of a label, and `--` as the third and fourth characters.
+pub fn normalize_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if !name.is_ascii() {
+        return Err(ClientError::InvalidName(format!(
+            "{name}: non-ASCII names are not supported"
+        )));
+    }
+
+    let name = name.to_ascii_lowercase();
+    for label in name.split('.') {
+        if label.is_empty() {
+            return Err(ClientError::InvalidName(format!("{name}: empty label")));
+        }
+        if let Some(c) = label
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_')
+        {
+            return Err(ClientError::InvalidName(format!(
+                "{name}: disallowed character {c:?}"
+            )));
+        }
+        if label.trim_start_matches('_').contains('_') {
+            return Err(ClientError::InvalidName(format!(
+                "{name}: underscore allowed only at the start of a label"
+            )));
+        }
+        if label.get(2..4) == Some("--") {
+            return Err(ClientError::InvalidName(format!(
+                "{name}: invalid label extension {label}"
+            )));
+        }
+    }
+    Ok(name)
+}
+
+/// Computes the ENS namehash of an already normalized name.
+pub fn namehash(name: &str) -> B256 {
+    if name.is_empty() {
+        return B256::ZERO;
+    }
+    name.rsplit('.').fold(B256::ZERO, |node, label| {
+        keccak256([node.as_slice(), keccak256(label).as_slice()].concat())
+    })
+}
+
+/// Returns the reverse-resolution name of `address` (`<hex address>.addr.reverse`).
+pub(crate) fn reverse_name(address: Address) -> String {
+    format!("{address:x}.addr.reverse")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::{namehash, normalize_name};
+    use crate::ClientError;
+
+    #[test]
+    fn normalizes_ascii_names() {
+        assert_eq!(normalize_name(" Vitalik.ETH ").unwrap(), "vitalik.eth");
+        assert_eq!(
+            normalize_name("_dmarc.nick.eth").unwrap(),
+            "_dmarc.nick.eth"
+        );
+        assert_eq!(normalize_name("my-name.eth").unwrap(), "my-name.eth");
+    }
+
+    #[test]
+    fn rejects_names_it_cannot_normalize() {
+        for name in [
+            "vitalik..eth",
+            "vi talik.eth",
+            "a_b.eth",
+            "xn--nxasmq6b.eth",
+            "ℌello.eth",
+            "vitаlik.eth", // Cyrillic "а"
+            "💩.eth",
+        ] {
+            assert!(
+                matches!(normalize_name(name), Err(ClientError::InvalidName(_))),
+                "{name} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn hashes_names() {
+        assert_eq!(
+            namehash(""),
+            b256!("0x0000000000000000000000000000000000000000000000000000000000000000")
+        );
+        assert_eq!(
+            namehash("eth"),
+            b256!("0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            b256!("0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+        );
+    }
+}
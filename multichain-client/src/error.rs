@@ -0,0 +1,147 @@
+//! Error type returned by every fallible operation in this crate.
+
+use alloy::transports::{RpcError, TransportErrorKind};
+use foundry_block_explorers::errors::EtherscanError;
+
+/// A specialized [`Result`](std::result::Result) type for [`ClientError`].
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+/// Errors returned by [`EvmChainClient`](crate::EvmChainClient) and
+/// [`EvmClientRegistry`](crate::EvmClientRegistry).
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The RPC endpoint could not be reached, timed out, or returned an HTTP error.
+    #[error("RPC transport error: {0}")]
+    Transport(String),
+
+    /// The RPC endpoint answered with a JSON-RPC error.
+    #[error("RPC error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code (e.g. `-32005` for rate limits)
+        code: i64,
+        /// Error message returned by the node
+        message: String,
+    },
+
+    /// A contract call reverted.
+    #[error("Execution reverted: {0}")]
+    Reverted(String),
+
+    /// The RPC endpoint rejected the request because its rate limit was exceeded.
+    #[error("RPC rate limit exceeded: {0}")]
+    RpcRateLimited(String),
+
+    /// The block explorer rejected the request because its rate limit was exceeded.
+    #[error("Explorer rate limit exceeded")]
+    ExplorerRateLimited,
+
+    /// The block explorer has no transactions for the requested address.
+    #[error("No transactions found")]
+    NoTransactions,
+
+    /// The block explorer returned an error.
+    #[error("Explorer API error: {0}")]
+    Explorer(#[source] EtherscanError),
+
+    /// The requested block, transaction or other on-chain object does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The contract at an address is not a usable ERC-20 token.
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+
+    /// A per-chain limit was reached (e.g. the number of tracked tokens).
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    /// An ENS name could not be normalized.
+    #[error("Invalid ENS name: {0}")]
+    InvalidName(String),
+
+    /// A response could not be decoded.
+    #[error("Failed to decode response: {0}")]
+    Decode(String),
+
+    /// The operation is not available on this chain (e.g. history without a transaction source).
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// The client was configured incorrectly (e.g. invalid RPC URL or unsupported chain).
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+}
+
+impl From<RpcError<TransportErrorKind>> for ClientError {
+    fn from(err: RpcError<TransportErrorKind>) -> Self {
+        match err {
+            RpcError::ErrorResp(payload) if is_revert(payload.code, &payload.message) => {
+                Self::Reverted(payload.message.into_owned())
+            }
+            RpcError::ErrorResp(payload) if is_rate_limit(payload.code, &payload.message) => {
+                Self::RpcRateLimited(payload.message.into_owned())
+            }
+            RpcError::ErrorResp(payload) => Self::Rpc {
+                code: payload.code,
+                message: payload.message.into_owned(),
+            },
+            RpcError::SerError(err) => Self::Decode(err.to_string()),
+            RpcError::DeserError { err, .. } => Self::Decode(err.to_string()),
+            RpcError::NullResp => Self::Decode("Unexpected null response".to_string()),
+            RpcError::Transport(TransportErrorKind::HttpError(err)) if err.is_rate_limit_err() => {
+                Self::RpcRateLimited(err.body)
+            }
+            err => Self::Transport(err.to_string()),
+        }
+    }
+}
+
+/// Returns `true` if a JSON-RPC error reports a reverted call.
+///
+/// Geth and most clients use code 3 when the revert carries data, and `-32000` with an
+/// "execution reverted" message when it does not.
+fn is_revert(code: i64, message: &str) -> bool {
+    code == 3 || message.starts_with("execution reverted")
+}
+
+/// Returns `true` if a JSON-RPC error reports an exceeded rate limit.
+///
+/// Providers disagree on the code (Infura also uses `-32005` for too many `eth_getLogs`
+/// results), so apart from 429 the message decides.
+fn is_rate_limit(code: i64, message: &str) -> bool {
+    let message = message.to_lowercase();
+    code == 429 || message.contains("rate limit") || message.contains("rate exceeded")
+}
+
+impl From<EtherscanError> for ClientError {
+    fn from(err: EtherscanError) -> Self {
+        match err {
+            EtherscanError::RateLimitExceeded => Self::ExplorerRateLimited,
+            EtherscanError::ErrorResponse { ref message, .. }
+                if message.starts_with("No transactions found") =>
+            {
+                Self::NoTransactions
+            }
+            EtherscanError::ContractCodeNotVerified(address) => {
+                Self::NotFound(format!("Verified source code of {address}"))
+            }
+            EtherscanError::ChainNotSupported(_)
+            | EtherscanError::LocalNetworksNotSupported
+            | EtherscanError::InvalidApiKey
+            | EtherscanError::Builder(_) => Self::Config(err.to_string()),
+            err => Self::Explorer(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+impl From<alloy::sol_types::Error> for ClientError {
+    fn from(err: alloy::sol_types::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
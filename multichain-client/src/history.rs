@@ -0,0 +1,328 @@
+//! Pluggable sources of transaction history.
+//!
+//! RPC nodes cannot list the transactions of an address, so [`EvmChainClient`] reads history,
+//! and the verified ABIs of contracts, from an indexer behind the [`TransactionSource`] trait:
+//! - [`EtherscanSource`] uses the Etherscan V2 multichain API
+//! - [`BlockscoutSource`] uses the Etherscan-compatible API of a Blockscout instance
+//! - [`NoTransactionSource`] is used for chains without an indexer (e.g. local devnets), so they
+//!   can still be loaded with RPC-only features
+//!
+//! [`EvmChainClient`]: crate::EvmChainClient
+
+use std::fmt;
+
+use alloy::{json_abi::JsonAbi, primitives::Address, transports::http::reqwest::Url};
+use alloy_chains::Chain;
+use foundry_block_explorers::{
+    Client as EtherscanClient, EtherscanApiVersion,
+    account::{
+        ERC20TokenTransferEvent, InternalTransaction, InternalTxQueryOption, NormalTransaction,
+        TokenQueryOption, TxListParams,
+    },
+    errors::EtherscanError,
+};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ClientError, Result},
+    rate_limit::{RateLimit, RateLimiter},
+    retry::{RetryPolicy, is_retryable_explorer_error},
+};
+
+/// Which indexer a chain reads its transaction history from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ExplorerConfig {
+    /// The Etherscan V2 API, using the API key passed when creating the client
+    Etherscan,
+
+    /// A Blockscout instance
+    Blockscout {
+        /// Base URL of the instance (e.g., "https://eth.blockscout.com")
+        url: String,
+
+        /// Optional API key, for instances that require one or grant higher limits with one
+        #[serde(rename = "apiKey", default, skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+    },
+
+    /// No transaction history
+    None,
+}
+
+/// A source of transaction history for a single chain.
+///
+/// Implementations return the explorer's "No transactions found" response as
+/// [`ClientError::NoTransactions`]; pagination is driven by the caller through `params`.
+pub trait TransactionSource: fmt::Debug + Send + Sync {
+    /// Human-readable name of the source, used in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Lists normal transactions sent from or to `address`.
+    fn transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>>;
+
+    /// Lists internal (contract-originated) transactions involving `address`.
+    fn internal_transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>>;
+
+    /// Lists ERC-20 transfers sent from or to `address`.
+    fn token_transfers(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>>;
+
+    /// Fetches the verified ABI of the contract at `address`.
+    ///
+    /// Fails with [`ClientError::NotFound`] if the contract source is not verified.
+    fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>>;
+}
+
+/// An Etherscan-compatible account API, with rate limiting and retries.
+#[derive(Debug)]
+struct ExplorerApi {
+    client: EtherscanClient,
+    limiter: RateLimiter,
+    retry: RetryPolicy,
+}
+
+impl ExplorerApi {
+    /// Sends a request through the rate limiter, retrying transient failures.
+    async fn request<'a, T, F, Fut>(&'a self, op: F) -> Result<T>
+    where
+        F: Fn(&'a EtherscanClient) -> Fut,
+        Fut: Future<Output = Result<T, EtherscanError>>,
+    {
+        self.retry
+            .retry(
+                || async {
+                    self.limiter.acquire().await;
+                    op(&self.client).await
+                },
+                is_retryable_explorer_error,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> Result<Vec<NormalTransaction>> {
+        self.request(|client| client.get_transactions(&address, Some(params)))
+            .await
+    }
+
+    async fn internal_transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> Result<Vec<InternalTransaction>> {
+        self.request(|client| {
+            client
+                .get_internal_transactions(InternalTxQueryOption::ByAddress(address), Some(params))
+        })
+        .await
+    }
+
+    async fn token_transfers(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        self.request(|client| {
+            client
+                .get_erc20_token_transfer_events(TokenQueryOption::ByAddress(address), Some(params))
+        })
+        .await
+    }
+
+    async fn contract_abi(&self, address: Address) -> Result<JsonAbi> {
+        self.request(|client| client.contract_abi(address)).await
+    }
+}
+
+/// A [`TransactionSource`] backed by an Etherscan-compatible account API.
+trait ExplorerSource: fmt::Debug + Send + Sync {
+    /// Name returned by [`TransactionSource::name`].
+    const NAME: &str;
+
+    fn api(&self) -> &ExplorerApi;
+}
+
+impl<S: ExplorerSource> TransactionSource for S {
+    fn name(&self) -> &str {
+        S::NAME
+    }
+
+    fn transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+        Box::pin(self.api().transactions(address, params))
+    }
+
+    fn internal_transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+        Box::pin(self.api().internal_transactions(address, params))
+    }
+
+    fn token_transfers(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+        Box::pin(self.api().token_transfers(address, params))
+    }
+
+    fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+        Box::pin(self.api().contract_abi(address))
+    }
+}
+
+/// Transaction history from the Etherscan V2 multichain API.
+#[derive(Debug)]
+pub struct EtherscanSource(ExplorerApi);
+
+impl EtherscanSource {
+    /// Constructs a new `EtherscanSource` for `chain`.
+    ///
+    /// Requests are throttled to `rate_limit` by the explorer rate limiter shared by every client
+    /// using `api_key`. Fails with [`ClientError::Config`] if Etherscan does not support the
+    /// chain.
+    pub fn new(
+        chain: Chain,
+        api_key: &str,
+        retry: RetryPolicy,
+        rate_limit: RateLimit,
+    ) -> Result<Self> {
+        Ok(Self(ExplorerApi {
+            client: EtherscanClient::new(chain, api_key)?,
+            limiter: RateLimiter::for_api_key(api_key, rate_limit),
+            retry,
+        }))
+    }
+}
+
+impl ExplorerSource for EtherscanSource {
+    const NAME: &str = "Etherscan";
+
+    fn api(&self) -> &ExplorerApi {
+        &self.0
+    }
+}
+
+/// Transaction history from the Etherscan-compatible `/api` endpoint of a Blockscout instance.
+#[derive(Debug)]
+pub struct BlockscoutSource(ExplorerApi);
+
+impl BlockscoutSource {
+    /// Constructs a new `BlockscoutSource` for the instance at `url`.
+    ///
+    /// Requests are throttled to `rate_limit` by an explorer rate limiter shared by every client
+    /// using the same API key, or the same instance when no key is given.
+    pub fn new(
+        url: &str,
+        api_key: Option<&str>,
+        retry: RetryPolicy,
+        rate_limit: RateLimit,
+    ) -> Result<Self> {
+        let invalid_url = |e| ClientError::Config(format!("Invalid Blockscout URL {url}: {e}"));
+        let url = Url::parse(url).map_err(invalid_url)?;
+        let api_url = url.join("api").map_err(invalid_url)?;
+        let client = EtherscanClient::builder()
+            .with_api_version(EtherscanApiVersion::V1)
+            .with_api_key(api_key.unwrap_or_default())
+            .with_url(url.clone())?
+            .with_api_url(api_url)?
+            .build()?;
+
+        Ok(Self(ExplorerApi {
+            client,
+            limiter: RateLimiter::for_api_key(api_key.unwrap_or(url.as_str()), rate_limit),
+            retry,
+        }))
+    }
+}
+
+impl ExplorerSource for BlockscoutSource {
+    const NAME: &str = "Blockscout";
+
+    fn api(&self) -> &ExplorerApi {
+        &self.0
+    }
+}
+
+/// A source for chains without an indexer; every request fails with
+/// [`ClientError::Unsupported`].
+#[derive(Debug)]
+pub struct NoTransactionSource {
+    chain: String,
+}
+
+impl NoTransactionSource {
+    /// Constructs a new `NoTransactionSource` for the chain named `chain`.
+    pub fn new(chain: impl Into<String>) -> Self {
+        Self {
+            chain: chain.into(),
+        }
+    }
+
+    fn unsupported<T>(&self) -> BoxFuture<'_, Result<T>>
+    where
+        T: Send + 'static,
+    {
+        let err = ClientError::Unsupported(format!(
+            "No transaction source configured for chain: {}",
+            self.chain
+        ));
+        Box::pin(async move { Err(err) })
+    }
+}
+
+impl TransactionSource for NoTransactionSource {
+    fn name(&self) -> &str {
+        "none"
+    }
+
+    fn transactions(
+        &self,
+        _address: Address,
+        _params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+        self.unsupported()
+    }
+
+    fn internal_transactions(
+        &self,
+        _address: Address,
+        _params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+        self.unsupported()
+    }
+
+    fn token_transfers(
+        &self,
+        _address: Address,
+        _params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+        self.unsupported()
+    }
+
+    fn contract_abi(&self, _address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+        self.unsupported()
+    }
+}
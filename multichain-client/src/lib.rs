@@ -1,13 +1,64 @@
 //! This library provides a modular interface for interacting with multiple EVM-compatible
 //! chains using a generic client. It includes functionality to:
 //! - Parse JSON into metadata structs
-//! - Initialize JSON-RPC and Etherscan clients
-//! - Fetch native and token balances
+//! - Initialize JSON-RPC clients over HTTP or WebSocket, with failover across RPC endpoints
+//! - Run clients against scripted in-memory backends instead of live endpoints
+//! - Record RPC and explorer traffic to a cassette file and replay it offline
+//! - Read transaction history from Etherscan, Blockscout, or no indexer at all
+//! - Retry and rate limit RPC and explorer requests
+//! - Resolve ENS names to addresses, and addresses to verified primary names
+//! - Classify addresses as EOAs, EIP-7702 delegated EOAs, proxies, Safe wallets or contracts
+//! - Fetch native and token balances, batched through Multicall3 where available
+//! - Fetch transactions with their receipts and a breakdown of the fees they paid
+//! - Fetch ERC-721 NFT holdings and ERC-1155 balances
+//! - Fetch ERC-20 transfer history from block explorers
+//! - Decode transaction calldata and event logs from a local signature database or contract ABIs
+//! - Fetch verified contract ABIs, following EIP-1967 and EIP-1822 proxies, with an on-disk cache
+//! - Stream event logs over large block ranges in adaptively sized, concurrent chunks
+//! - Subscribe to new blocks and logs over WebSocket endpoints, reconnecting automatically
+//! - Send JSON-RPC batch requests
 //! - Aggregate transactions across multiple chains
 //!
 
+mod abi;
+mod backend;
+mod batch;
 mod client;
+mod decode;
+mod endpoint;
+mod ens;
+mod error;
+mod history;
+mod log_range;
 mod metadata;
+mod multicall;
+mod rate_limit;
+mod recording;
+mod retry;
+mod subscription;
+#[cfg(test)]
+mod test_utils;
 
+pub use abi::AbiCache;
+pub use backend::{ChainBackend, ScriptedBackend, ScriptedBatch, ScriptedResponse, WsBackend};
+pub use batch::{AccountState, RpcCall};
 pub use client::{EvmChainClient, EvmClientRegistry};
-pub use metadata::{ChainMetaData, NativeCurrency, TokenBalance, TokenMetadata};
+pub use decode::{DecodedArg, DecodedCall, DecodedLog, SignatureDatabase, decode_calldata};
+pub use endpoint::EndpointPool;
+pub use ens::{MAINNET_ENS_REGISTRY, namehash, normalize_name};
+pub use error::{ClientError, Result};
+pub use history::{
+    BlockscoutSource, EtherscanSource, ExplorerConfig, NoTransactionSource, TransactionSource,
+};
+pub use log_range::LogRangeFetcher;
+pub use metadata::{
+    AddressKind, BlockHead, ChainMetaData, Erc1155Balance, Erc1155Holding, FeeAmount, L2FeeModel,
+    NativeCurrency, NftCollectionMetadata, NftHolding, NftStandard, ProxyInfo, ProxyStandard,
+    SafeInfo, TokenBalance, TokenBalanceResult, TokenMetadata, TokenTransfer, TransactionDetails,
+    TransactionFees, TransactionStatus,
+};
+pub use multicall::MULTICALL3_ADDRESS;
+pub use rate_limit::{RateLimit, RateLimiter};
+pub use recording::{Cassette, RecordingBackend, RecordingSource, ReplayBackend, ReplaySource};
+pub use retry::{RetryLayer, RetryPolicy, RetryService};
+pub use subscription::Subscriptions;
@@ -0,0 +1,282 @@
+//! Adaptive `eth_getLogs` fetching over large block ranges.
+//!
+//! Public RPCs reject `eth_getLogs` requests spanning too many blocks or returning too many
+//! logs. [`LogRangeFetcher`] splits a range into chunks no larger than the chain's maximum,
+//! fetches a bounded number of them concurrently, and halves any chunk the RPC rejects as too
+//! large. Later chunks start from the smallest size that worked, so a busy contract only costs a
+//! few failed requests, and the size doubles back towards the maximum after each request that
+//! succeeds at it, so a busy stretch of blocks doesn't slow down the rest of the range.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use alloy::rpc::{
+    client::ReqwestClient,
+    types::{Filter, Log},
+};
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+
+use crate::error::{ClientError, Result};
+
+/// Default maximum number of blocks requested per `eth_getLogs` call.
+pub(crate) const DEFAULT_MAX_LOG_RANGE: u64 = 10_000;
+
+/// Default number of `eth_getLogs` calls in flight at once.
+pub(crate) const DEFAULT_LOG_CONCURRENCY: usize = 4;
+
+/// Error messages RPCs return when an `eth_getLogs` range is too large for them.
+const RANGE_TOO_LARGE_ERRORS: &[&str] = &[
+    "too many results",
+    "query returned more than",
+    "response size exceeded",
+    "response too large",
+    "block range",
+    "range too large",
+    "range is too large",
+];
+
+/// Fetches the logs of large block ranges in adaptively sized chunks.
+///
+/// Obtained from [`EvmChainClient::log_fetcher`](crate::EvmChainClient::log_fetcher), which
+/// applies the chain's `maxLogRange` and `maxLogConcurrency` settings.
+#[derive(Clone, Debug)]
+pub struct LogRangeFetcher {
+    rpc_client: Arc<ReqwestClient>,
+    max_range: u64,
+    concurrency: usize,
+}
+
+impl LogRangeFetcher {
+    /// Constructs a new `LogRangeFetcher` with the default maximum range and concurrency.
+    pub fn new(rpc_client: Arc<ReqwestClient>) -> Self {
+        Self {
+            rpc_client,
+            max_range: DEFAULT_MAX_LOG_RANGE,
+            concurrency: DEFAULT_LOG_CONCURRENCY,
+        }
+    }
+
+    /// Sets the maximum number of blocks requested per `eth_getLogs` call.
+    pub fn with_max_range(mut self, max_range: u64) -> Self {
+        self.max_range = max_range.max(1);
+        self
+    }
+
+    /// Sets the number of `eth_getLogs` calls in flight at once.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Streams the logs matching `filter` between `from_block` and `to_block` (inclusive), in
+    /// block order.
+    ///
+    /// A chunk the RPC rejects as too large is split in half until it succeeds or is a single
+    /// block; any other error is yielded in place of the chunk's logs. Once a chunk succeeds,
+    /// the following ones are allowed twice its size again, up to the maximum range.
+    pub fn stream(
+        &self,
+        filter: Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> impl Stream<Item = Result<Log>> + Send + 'static {
+        let rpc_client = self.rpc_client.clone();
+        let max_range = self.max_range;
+        let range = Arc::new(AtomicU64::new(self.max_range));
+
+        let chunks = stream::unfold(Some(from_block), {
+            let range = range.clone();
+            move |start| {
+                let next = start.filter(|start| *start <= to_block).map(|start| {
+                    let size = range.load(Ordering::Relaxed);
+                    let end = start.saturating_add(size - 1).min(to_block);
+                    ((start, end), end.checked_add(1))
+                });
+                async move { next }
+            }
+        });
+
+        chunks
+            .map(move |(start, end)| {
+                fetch_chunk(
+                    rpc_client.clone(),
+                    filter.clone(),
+                    start,
+                    end,
+                    range.clone(),
+                    max_range,
+                )
+            })
+            .buffered(self.concurrency)
+            .map_ok(|logs| stream::iter(logs.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Fetches every log matching `filter` between `from_block` and `to_block` (inclusive).
+    pub async fn fetch(&self, filter: Filter, from_block: u64, to_block: u64) -> Result<Vec<Log>> {
+        self.stream(filter, from_block, to_block)
+            .try_collect()
+            .await
+    }
+}
+
+/// Fetches the logs of one chunk, halving sub-ranges the RPC rejects as too large.
+///
+/// The shared `range` used by the chunks that follow is lowered to the size of the halves, and
+/// doubled, up to `max_range`, whenever a request at the current size succeeds.
+async fn fetch_chunk(
+    rpc_client: Arc<ReqwestClient>,
+    filter: Filter,
+    start: u64,
+    end: u64,
+    range: Arc<AtomicU64>,
+    max_range: u64,
+) -> Result<Vec<Log>> {
+    let mut logs = Vec::new();
+    // a stack of sub-ranges, with the earliest on top so logs stay in block order
+    let mut pending = vec![(start, end)];
+    while let Some((start, end)) = pending.pop() {
+        let request = filter.clone().from_block(start).to_block(end);
+        match rpc_client
+            .request::<_, Vec<Log>>("eth_getLogs", (request,))
+            .await
+            .map_err(ClientError::from)
+        {
+            Ok(chunk) => {
+                logs.extend(chunk);
+                // smaller requests (the end of the range, or halves of a chunk that started
+                // before the range was lowered) say nothing about the current size
+                let size = end - start + 1;
+                let _ = range.try_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                    (size >= current && current < max_range)
+                        .then(|| current.saturating_mul(2).min(max_range))
+                });
+            }
+            Err(err) if end > start && is_range_too_large(&err) => {
+                let mid = start + (end - start) / 2;
+                range.fetch_min(mid - start + 1, Ordering::Relaxed);
+                pending.push((mid + 1, end));
+                pending.push((start, mid));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(logs)
+}
+
+/// Returns `true` if `err` is an RPC rejecting an `eth_getLogs` range as too large.
+fn is_range_too_large(err: &ClientError) -> bool {
+    let ClientError::Rpc { message, .. } = err else {
+        return false;
+    };
+    let message = message.to_lowercase();
+    RANGE_TOO_LARGE_ERRORS
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bytes},
+        rpc::types::{Filter, Log},
+    };
+    use serde_json::Value;
+
+    use crate::{
+        ClientError, ScriptedBackend, ScriptedResponse,
+        test_utils::{chain_metadata, scripted_client},
+    };
+
+    /// Blocks below this each hold one log; later blocks hold none.
+    const BUSY_UNTIL: u64 = 16;
+    /// Number of logs above which the scripted RPC rejects a request.
+    const MAX_RESULTS: u64 = 4;
+
+    fn requested_range(params: &Value) -> (u64, u64) {
+        let filter: Filter = serde_json::from_value(params[0].clone()).unwrap();
+        (
+            filter.get_from_block().unwrap(),
+            filter.get_to_block().unwrap(),
+        )
+    }
+
+    /// Scripts `eth_getLogs` to reject requests matching more than [`MAX_RESULTS`] logs.
+    fn scripted_logs() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend.on_request("eth_getLogs", |params: &Value| {
+            let (from, to) = requested_range(params);
+            let logs: Vec<Log> = (from..=to.min(BUSY_UNTIL - 1))
+                .map(|block| Log {
+                    inner: alloy::primitives::Log::new_unchecked(
+                        Address::ZERO,
+                        vec![],
+                        Bytes::new(),
+                    ),
+                    block_number: Some(block),
+                    ..Default::default()
+                })
+                .collect();
+            if logs.len() as u64 > MAX_RESULTS {
+                return ScriptedResponse::error(
+                    -32005,
+                    format!("query returned more than {MAX_RESULTS} results"),
+                );
+            }
+            ScriptedResponse::result(logs)
+        });
+        backend
+    }
+
+    #[tokio::test]
+    async fn halves_rejected_chunks_and_grows_back() {
+        let backend = scripted_logs();
+        let mut metadata = chain_metadata("test", 1);
+        metadata.max_log_range = Some(16);
+        // one chunk at a time, so the requests are sent in a predictable order
+        metadata.max_log_concurrency = Some(1);
+        let client = scripted_client(metadata, &backend, vec![]);
+
+        let logs = client.get_logs(&Filter::new(), 0, 47).await.unwrap();
+        let blocks: Vec<_> = logs.iter().map(|log| log.block_number.unwrap()).collect();
+        assert_eq!(blocks, (0..BUSY_UNTIL).collect::<Vec<_>>());
+
+        let ranges: Vec<_> = backend
+            .requests()
+            .iter()
+            .map(|request| requested_range(&request.params))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (0, 15),
+                (0, 7),
+                (0, 3),
+                (4, 7),
+                (8, 15),
+                (8, 11),
+                (12, 15),
+                // past the busy blocks, the range doubles back to the maximum
+                (16, 23),
+                (24, 39),
+                (40, 47),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_split() {
+        let backend = ScriptedBackend::new();
+        backend.on(
+            "eth_getLogs",
+            ScriptedResponse::error(-32000, "header not found"),
+        );
+        let client = scripted_client(chain_metadata("test", 1), &backend, vec![]);
+
+        let err = client.get_logs(&Filter::new(), 0, 100).await.unwrap_err();
+        assert!(matches!(err, ClientError::Rpc { .. }));
+        assert_eq!(backend.requests().len(), 1);
+    }
+}
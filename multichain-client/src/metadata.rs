@@ -2,6 +2,7 @@
 //!
 //! This module provides functionality to:
 //! - Load token metadata from a local JSON file
+//! - Load NFT collection metadata from a local JSON file
 //! - Load a list of chain metadata from a JSON file
 //!
 //! The format for chain metadata matches the format used by chainlist.org:
@@ -16,8 +17,16 @@
 //! }
 //! ```
 
+mod account;
+mod block;
 mod chain;
+mod nft;
 mod token;
+mod transaction;
 
-pub use chain::{ChainMetaData, NativeCurrency};
-pub use token::{TokenBalance, TokenMetadata};
+pub use account::{AddressKind, ProxyInfo, ProxyStandard, SafeInfo};
+pub use block::BlockHead;
+pub use chain::{ChainMetaData, L2FeeModel, NativeCurrency};
+pub use nft::{Erc1155Balance, Erc1155Holding, NftCollectionMetadata, NftHolding, NftStandard};
+pub use token::{TokenBalance, TokenBalanceResult, TokenMetadata, TokenTransfer};
+pub use transaction::{FeeAmount, TransactionDetails, TransactionFees, TransactionStatus};
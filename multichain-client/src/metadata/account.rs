@@ -0,0 +1,71 @@
+//! Types describing what kind of account an address is.
+
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// The kind of account at an address, as detected by
+/// [`EvmChainClient::classify_address`](crate::EvmChainClient::classify_address).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AddressKind {
+    /// An externally owned account, without code
+    Eoa,
+
+    /// An EOA that delegated its code to a contract with EIP-7702
+    Delegated {
+        /// Contract whose code the EOA runs
+        delegate: Address,
+    },
+
+    /// An upgradeable proxy contract
+    Proxy(ProxyInfo),
+
+    /// A Safe (formerly Gnosis Safe) multisig wallet
+    Safe(SafeInfo),
+
+    /// Any other contract
+    Contract,
+}
+
+/// The standard an upgradeable proxy follows to store its implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyStandard {
+    /// Implementation address in the EIP-1967 implementation slot
+    Eip1967,
+    /// Beacon address in the EIP-1967 beacon slot, which returns the implementation
+    Beacon,
+    /// Implementation address in the EIP-1822 (UUPS) `PROXIABLE` slot
+    Eip1822,
+}
+
+/// An upgradeable proxy and the implementation it currently delegates to.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProxyInfo {
+    /// Standard the proxy follows
+    pub standard: ProxyStandard,
+
+    /// Contract the proxy delegates calls to
+    pub implementation: Address,
+
+    /// Beacon the implementation is read from, for beacon proxies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beacon: Option<Address>,
+}
+
+/// The configuration of a Safe multisig wallet.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SafeInfo {
+    /// Safe singleton (master copy) the wallet's proxy delegates to
+    pub singleton: Address,
+
+    /// Version reported by the singleton (e.g., "1.3.0")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Owners of the wallet
+    pub owners: Vec<Address>,
+
+    /// Number of owner signatures required to execute a transaction
+    pub threshold: u64,
+}
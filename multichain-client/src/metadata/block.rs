@@ -0,0 +1,20 @@
+//! Types describing blocks.
+
+use alloy::primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// The header of a new block, as announced by a `newHeads` subscription.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BlockHead {
+    /// Block number
+    pub number: u64,
+
+    /// Block hash
+    pub hash: B256,
+
+    /// UNIX timestamp of the block
+    pub timestamp: u64,
+
+    /// Base fee of the block, in wei; `None` before London
+    pub base_fee_per_gas: Option<u128>,
+}
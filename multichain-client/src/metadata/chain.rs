@@ -3,16 +3,25 @@
 
 use std::sync::Arc;
 
-use alloy::{
-    rpc::client::{ClientBuilder, ReqwestClient},
-    transports::http::reqwest::Url,
-};
+use alloy::{primitives::Address, transports::http::reqwest::Url};
 use alloy_chains::Chain;
-use anyhow::{Context, Result};
-use foundry_block_explorers::Client as EtherscanClient;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
-use crate::{EvmChainClient, metadata::TokenMetadata};
+use crate::{
+    EvmChainClient,
+    backend::WsBackend,
+    endpoint::EndpointPool,
+    error::{ClientError, Result},
+    history::{
+        BlockscoutSource, EtherscanSource, ExplorerConfig, NoTransactionSource, TransactionSource,
+    },
+    metadata::TokenMetadata,
+    rate_limit::{DEFAULT_EXPLORER_RATE_LIMIT, RateLimit},
+    recording::{Cassette, RecordingBackend, RecordingSource, ReplayBackend, ReplaySource},
+    retry::RetryPolicy,
+    subscription::Subscriptions,
+};
 
 /// Metadata describing a chain's native currency (e.g., ETH, MATIC, etc.)
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -25,6 +34,18 @@ pub struct NativeCurrency {
     pub decimals: u64,
 }
 
+/// How an L2 charges for posting its transactions' data to L1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum L2FeeModel {
+    /// OP-stack chains (e.g., Optimism, Base): receipts report the L1 data fee as `l1Fee`, on
+    /// top of the L2 execution fee
+    OpStack,
+    /// Arbitrum chains: receipts report the share of `gasUsed` spent on L1 data as
+    /// `gasUsedForL1`
+    Arbitrum,
+}
+
 /// Chain metadata, usually parsed from chain-list JSON files.
 ///
 /// Includes basic chain identity and a list of RPC endpoints.
@@ -51,63 +72,256 @@ pub struct ChainMetaData {
 
     /// List of RPC URLs for the chain
     ///
-    /// The first valid `http(s)` URL without template variables will be used.
+    /// All valid `http(s)` and `ws(s)` URLs without template variables are used, in order, with
+    /// failover. WebSocket URLs also serve block and log subscriptions.
     pub rpc: Vec<String>,
+
+    /// Address of a Multicall3 deployment on the chain, if any
+    ///
+    /// When set, token balances are batched through `aggregate3` instead of one `eth_call` per
+    /// token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multicall: Option<Address>,
+
+    /// Maximum number of requests sent in a single JSON-RPC batch
+    ///
+    /// Defaults to 50 when unset. A value of `1` disables batching for endpoints that reject it.
+    #[serde(
+        rename = "maxBatchSize",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_batch_size: Option<usize>,
+
+    /// Maximum number of blocks requested per `eth_getLogs` call
+    ///
+    /// Defaults to 10,000 when unset. Ranges the RPC still rejects are halved automatically.
+    #[serde(
+        rename = "maxLogRange",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_log_range: Option<u64>,
+
+    /// Maximum number of `eth_getLogs` calls in flight when scanning a block range
+    ///
+    /// Defaults to 4 when unset.
+    #[serde(
+        rename = "maxLogConcurrency",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_log_concurrency: Option<usize>,
+
+    /// Retry policy for RPC and explorer requests
+    ///
+    /// Defaults to [`RetryPolicy::default`] when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+
+    /// Token-bucket rate limit for RPC requests
+    ///
+    /// RPC requests are not rate limited when unset.
+    #[serde(rename = "rateLimit", default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Token-bucket rate limit for explorer requests
+    ///
+    /// Defaults to 5 requests per second, the Etherscan free tier. Chains using the same API key
+    /// and limit share a single bucket.
+    #[serde(
+        rename = "explorerRateLimit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_rate_limit: Option<RateLimit>,
+
+    /// Indexer used for transaction history
+    ///
+    /// Defaults to Etherscan for chains it supports, and to no history otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer: Option<ExplorerConfig>,
+
+    /// Address of an ENS registry on the chain
+    ///
+    /// Defaults to the mainnet registry on chain ID 1; set it to resolve names against another
+    /// deployment, such as one on a local devnet.
+    #[serde(
+        rename = "ensRegistry",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ens_registry: Option<Address>,
+
+    /// L1 data fee model of an L2
+    ///
+    /// When unset, fees are computed from L2 gas alone, which undercounts the cost of L2
+    /// transactions.
+    #[serde(
+        rename = "l2FeeModel",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub l2_fee_model: Option<L2FeeModel>,
 }
 
 impl ChainMetaData {
     /// Create a new [`EvmChainClient`] from this metadata.
     ///
-    /// Uses the first available HTTP RPC URL that doesn't contain any template variables (e.g. `{API_KEY}`).
+    /// Uses every HTTP and WebSocket RPC URL that doesn't contain any template variables (e.g.
+    /// `{API_KEY}`), failing over between them in the listed order. WebSocket URLs are also used
+    /// for [`EvmChainClient::subscriptions`].
     ///
     /// # Arguments
-    /// * `etherscan_api_key` - A valid Etherscan API key for the corresponding chain.
+    /// * `etherscan_api_key` - A valid Etherscan API key, used when the chain reads its history
+    ///   from Etherscan.
     pub fn create_rpc_client(&self, etherscan_api_key: &str) -> Result<EvmChainClient> {
-        let http_url = self
-            .rpc
-            .iter()
-            .find(|url| url.starts_with("http") && !url.contains('{')) // skip urls that require templating
-            .context(format!("No usable RPC HTTP URL for chain: {}", self.name))?;
-
-        let url = Url::parse(http_url)?;
-        let rpc_client: ReqwestClient = ClientBuilder::default().http(url);
-        let etherscan = EtherscanClient::new(Chain::from_id(self.chain_id), etherscan_api_key)?;
-
-        Ok(EvmChainClient::new(
-            self.clone(),
-            Arc::new(rpc_client),
-            Arc::new(etherscan),
-            vec![],
-        ))
+        self.create_rpc_client_with_tokens(etherscan_api_key, vec![])
     }
 
     /// Create a new [`EvmChainClient`] from this metadata, using a pre-initialized list of tokens.
     ///
-    /// Uses the first available HTTP RPC URL that doesn't contain any template variables (e.g. `{API_KEY}`).
+    /// Uses every HTTP and WebSocket RPC URL that doesn't contain any template variables (e.g.
+    /// `{API_KEY}`), failing over between them in the listed order. WebSocket URLs are also used
+    /// for [`EvmChainClient::subscriptions`].
     ///
     /// # Arguments
-    /// * `etherscan_api_key` - A valid Etherscan API key for the corresponding chain.
+    /// * `etherscan_api_key` - A valid Etherscan API key, used when the chain reads its history
+    ///   from Etherscan.
     /// * `tokens` - A list of `TokenMetadata` describing the tokens to track.
     pub fn create_rpc_client_with_tokens(
         &self,
         etherscan_api_key: &str,
         tokens: Vec<TokenMetadata>,
     ) -> Result<EvmChainClient> {
-        let http_url = self
-            .rpc
+        // requests and subscriptions share a single connection per WebSocket URL
+        let ws_backends: Vec<_> = self
+            .rpc_urls()?
             .iter()
-            .find(|url| url.starts_with("http") && !url.contains('{')) // skip urls that require templating
-            .context(format!("No usable RPC HTTP URL for chain: {}", self.name))?;
+            .filter(|url| matches!(url.scheme(), "ws" | "wss"))
+            .map(|url| WsBackend::new(url.as_str()))
+            .collect();
+        let client = EvmChainClient::with_backend(
+            self.clone(),
+            self.create_endpoint_pool(&ws_backends)?,
+            self.create_transaction_source(etherscan_api_key)?,
+            tokens,
+        );
 
-        let url = Url::parse(http_url)?;
-        let rpc_client: ReqwestClient = ClientBuilder::default().http(url);
-        let etherscan = EtherscanClient::new(Chain::from_id(self.chain_id), etherscan_api_key)?;
+        Ok(if ws_backends.is_empty() {
+            client
+        } else {
+            client.with_subscriptions(Subscriptions::with_backends(&self.short_name, ws_backends)?)
+        })
+    }
 
-        Ok(EvmChainClient::new(
+    /// Create a new [`EvmChainClient`] from this metadata that records every RPC and explorer
+    /// exchange to `cassette`.
+    ///
+    /// The recorded cassette can be replayed with [`Self::create_replay_client`], given the same
+    /// `tokens`.
+    pub fn create_recording_client(
+        &self,
+        etherscan_api_key: &str,
+        cassette: &Cassette,
+        tokens: Vec<TokenMetadata>,
+    ) -> Result<EvmChainClient> {
+        Ok(EvmChainClient::with_backend(
             self.clone(),
-            Arc::new(rpc_client),
-            Arc::new(etherscan),
+            RecordingBackend::new(self.create_endpoint_pool(&[])?, cassette.clone()),
+            Arc::new(RecordingSource::new(
+                self.create_transaction_source(etherscan_api_key)?,
+                cassette.clone(),
+            )),
             tokens,
         ))
     }
+
+    /// Create a new [`EvmChainClient`] from this metadata that answers every RPC and explorer
+    /// request from a recorded `cassette`, without network access.
+    ///
+    /// Only the balance reads of recorded tokens can be replayed, so `tokens` should be the list
+    /// passed to [`Self::create_recording_client`].
+    pub fn create_replay_client(
+        &self,
+        cassette: &Cassette,
+        tokens: Vec<TokenMetadata>,
+    ) -> EvmChainClient {
+        EvmChainClient::with_backend(
+            self.clone(),
+            ReplayBackend::new(cassette.clone()),
+            Arc::new(ReplaySource::new(cassette.clone())),
+            tokens,
+        )
+    }
+
+    /// Parses every usable HTTP and WebSocket RPC URL.
+    fn rpc_urls(&self) -> Result<Vec<Url>> {
+        self.rpc
+            .iter()
+            .filter(|url| url.starts_with("http") || url.starts_with("ws"))
+            .filter(|url| !url.contains('{')) // skip urls that require templating
+            .map(|url| {
+                Url::parse(url)
+                    .map_err(|e| ClientError::Config(format!("Invalid RPC URL {url}: {e}")))
+            })
+            .collect()
+    }
+
+    /// Creates an [`EndpointPool`] from every usable RPC URL, using the matching backends of
+    /// `ws_backends` for WebSocket URLs.
+    fn create_endpoint_pool(&self, ws_backends: &[WsBackend]) -> Result<EndpointPool> {
+        let urls = self.rpc_urls()?;
+        if urls.is_empty() {
+            return Err(ClientError::Config(format!(
+                "No usable RPC URL for chain: {}",
+                self.name
+            )));
+        }
+
+        EndpointPool::with_ws_backends(&self.short_name, urls, ws_backends)
+    }
+
+    /// Creates the [`TransactionSource`] described by `explorer`.
+    ///
+    /// When `explorer` is unset, chains that Etherscan does not support get a
+    /// [`NoTransactionSource`] instead of failing, so they can still be used for RPC-only
+    /// features.
+    fn create_transaction_source(
+        &self,
+        etherscan_api_key: &str,
+    ) -> Result<Arc<dyn TransactionSource>> {
+        let chain = Chain::from_id(self.chain_id);
+        let retry = self.retry.unwrap_or_default();
+        let rate_limit = self
+            .explorer_rate_limit
+            .unwrap_or(DEFAULT_EXPLORER_RATE_LIMIT);
+
+        Ok(match &self.explorer {
+            Some(ExplorerConfig::Etherscan) => Arc::new(EtherscanSource::new(
+                chain,
+                etherscan_api_key,
+                retry,
+                rate_limit,
+            )?),
+            Some(ExplorerConfig::Blockscout { url, api_key }) => Arc::new(BlockscoutSource::new(
+                url,
+                api_key.as_deref(),
+                retry,
+                rate_limit,
+            )?),
+            Some(ExplorerConfig::None) => Arc::new(NoTransactionSource::new(&self.name)),
+            None => match EtherscanSource::new(chain, etherscan_api_key, retry, rate_limit) {
+                Ok(source) => Arc::new(source),
+                Err(ClientError::Config(err)) => {
+                    warn!(
+                        "No transaction history for {}, Etherscan is unavailable: {err}",
+                        self.name
+                    );
+                    Arc::new(NoTransactionSource::new(&self.name))
+                }
+                Err(err) => return Err(err),
+            },
+        })
+    }
 }
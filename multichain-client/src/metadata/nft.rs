@@ -0,0 +1,73 @@
+//! Types related to ERC-721 and ERC-1155 NFT collections and holdings.
+
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+/// The token standard implemented by an NFT collection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NftStandard {
+    /// Non-fungible tokens, one owner per token ID
+    #[default]
+    Erc721,
+    /// Multi-tokens, a balance per owner and token ID
+    Erc1155,
+}
+
+/// Metadata about an ERC-721 or ERC-1155 NFT collection.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NftCollectionMetadata {
+    /// Human-readable collection name (e.g., "Bored Ape Yacht Club")
+    pub name: String,
+
+    /// Contract address of the collection on the chain
+    pub address: Address,
+
+    /// Collection symbol (e.g., "BAYC")
+    pub symbol: String,
+
+    /// Token standard of the collection, defaults to ERC-721
+    #[serde(default)]
+    pub standard: NftStandard,
+
+    /// Block the collection was deployed at
+    ///
+    /// Bounds the transfer log scans used to discover token IDs held by an address, so it is
+    /// required for ERC-1155 and non-enumerable ERC-721 collections.
+    #[serde(
+        rename = "startBlock",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_block: Option<u64>,
+}
+
+/// The NFTs of a single collection owned by a wallet address.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NftHolding {
+    /// Collection metadata (name, symbol, address)
+    pub collection: NftCollectionMetadata,
+
+    /// IDs of the tokens owned by the address being queried
+    pub token_ids: Vec<U256>,
+}
+
+/// The balance of a single ERC-1155 token ID.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Erc1155Balance {
+    /// ID of the token within the collection
+    pub token_id: U256,
+
+    /// Number of units of the token held
+    pub balance: U256,
+}
+
+/// The ERC-1155 tokens of a single collection held by a wallet address.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Erc1155Holding {
+    /// Collection metadata (name, symbol, address)
+    pub collection: NftCollectionMetadata,
+
+    /// Non-zero balances held by the address being queried
+    pub balances: Vec<Erc1155Balance>,
+}
@@ -1,8 +1,10 @@
-//! Types related to ERC-20 token metadata and balances.
+//! Types related to ERC-20 token metadata, balances and transfers.
 
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
 use serde::{Deserialize, Serialize};
 
+use crate::error::ClientError;
+
 /// Metadata about an ERC-20 token.
 ///
 /// This includes static, chain-specific details about the token.
@@ -32,3 +34,43 @@ pub struct TokenBalance {
     /// Balance of the token for the address being queried
     pub balance: U256,
 }
+
+/// The outcome of reading a single token balance.
+///
+/// Unlike [`TokenBalance`], failures are kept so callers can tell which tokens could not be read.
+#[derive(Debug)]
+pub struct TokenBalanceResult {
+    /// Token metadata (name, symbol, decimals, address)
+    pub token: TokenMetadata,
+
+    /// The balance, or the reason it could not be read
+    pub balance: Result<U256, ClientError>,
+}
+
+/// A single ERC-20 transfer to or from a wallet address, as reported by the block explorer.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TokenTransfer {
+    /// Hash of the transaction containing the transfer
+    pub hash: B256,
+
+    /// Number of the block containing the transfer
+    pub block_number: Option<u64>,
+
+    /// Unix timestamp of the block containing the transfer
+    pub timestamp: u64,
+
+    /// Sender of the tokens
+    pub from: Address,
+
+    /// Recipient of the tokens
+    pub to: Option<Address>,
+
+    /// Token metadata, taken from the tracked tokens when known and from the explorer otherwise
+    pub token: TokenMetadata,
+
+    /// Raw amount transferred, in the token's smallest unit
+    pub value: U256,
+
+    /// Amount transferred, formatted with the token's decimals (e.g., "1.5")
+    pub amount: String,
+}
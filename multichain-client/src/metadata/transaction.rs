@@ -0,0 +1,131 @@
+//! Types describing a single transaction, its receipt and the fees it paid.
+
+use alloy::{
+    primitives::{Address, B256, Bytes, U256},
+    rpc::types::Log,
+};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    /// Not mined yet
+    Pending,
+    /// Mined and executed successfully
+    Success,
+    /// Mined but reverted
+    Failed,
+    /// Mined before Byzantium, whose receipts do not report a status
+    Unknown,
+}
+
+/// A transaction with the details of its receipt, read over RPC.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransactionDetails {
+    /// Transaction hash
+    pub hash: B256,
+
+    /// Whether the transaction is pending, succeeded or reverted
+    pub status: TransactionStatus,
+
+    /// Number of the block containing the transaction, `None` while pending
+    pub block_number: Option<u64>,
+
+    /// EIP-2718 transaction type (e.g., 2 for EIP-1559, 3 for EIP-4844 blob transactions)
+    #[serde(rename = "type")]
+    pub tx_type: u8,
+
+    /// Sender of the transaction
+    pub from: Address,
+
+    /// Recipient of the transaction, `None` for contract creations
+    pub to: Option<Address>,
+
+    /// Address of the contract created by the transaction, if any
+    pub contract_address: Option<Address>,
+
+    /// Native value transferred, in the smallest unit
+    pub value: U256,
+
+    /// Sender nonce
+    pub nonce: u64,
+
+    /// Calldata
+    pub input: Bytes,
+
+    /// Gas limit set by the sender
+    pub gas_limit: u64,
+
+    /// Gas used by the transaction, `None` while pending
+    pub gas_used: Option<u64>,
+
+    /// Fees paid by the transaction, `None` while pending
+    pub fees: Option<TransactionFees>,
+
+    /// Logs emitted by the transaction
+    pub logs: Vec<Log>,
+}
+
+/// Breakdown of the fees paid by a mined transaction.
+///
+/// `total` is the sum of `burned`, `tip`, `blob` and `l1`. On Arbitrum, where the L1 data fee
+/// is charged as L2 gas, `burned` and `tip` only cover the gas spent on execution.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransactionFees {
+    /// Price paid per unit of gas, in wei
+    pub effective_gas_price: u128,
+
+    /// Base fee of the block, in wei; `None` before London
+    pub base_fee_per_gas: Option<u128>,
+
+    /// Tip paid to the block producer per unit of gas, in wei
+    pub priority_fee_per_gas: u128,
+
+    /// Blob gas used by an EIP-4844 transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<u64>,
+
+    /// Price paid per unit of blob gas, in wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_price: Option<u128>,
+
+    /// L1 gas used by the transaction's data, on OP-stack chains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_gas_used: Option<u64>,
+
+    /// L1 gas price the data fee was computed with, in wei, on OP-stack chains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_gas_price: Option<u128>,
+
+    /// Share of the gas used that paid for L1 data, on Arbitrum chains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used_for_l1: Option<u64>,
+
+    /// Everything the sender paid
+    pub total: FeeAmount,
+
+    /// Base fee burned for execution gas
+    pub burned: FeeAmount,
+
+    /// Priority fee paid to the block producer
+    pub tip: FeeAmount,
+
+    /// Blob fee burned by an EIP-4844 transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<FeeAmount>,
+
+    /// L1 data fee of an L2 transaction, on chains with an `l2FeeModel`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1: Option<FeeAmount>,
+}
+
+/// An amount of the chain's native currency.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FeeAmount {
+    /// Raw amount, in the smallest unit (wei)
+    pub value: U256,
+
+    /// Amount formatted with the native currency's decimals (e.g., "0.0021")
+    pub amount: String,
+}
@@ -0,0 +1,30 @@
+//! Bindings for the [Multicall3](https://github.com/mds1/multicall) contract.
+//!
+//! Multicall3 aggregates many read-only calls into a single `eth_call`, which keeps the number of
+//! RPC requests per wallet view independent of the number of tracked tokens.
+
+use alloy::{
+    primitives::{Address, address},
+    sol,
+};
+
+/// Address Multicall3 is deployed at on most EVM chains.
+pub const MULTICALL3_ADDRESS: Address = address!("0xcA11bde05977b3631167028862bE2a173976CA11");
+
+/// Maximum number of calls aggregated into a single `aggregate3` request.
+pub(crate) const MULTICALL_CHUNK_SIZE: usize = 100;
+
+sol! {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct Call3Result {
+        bool success;
+        bytes returnData;
+    }
+
+    function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+}
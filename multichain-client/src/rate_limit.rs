@@ -0,0 +1,168 @@
+//! Token-bucket rate limiting for RPC and explorer requests.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// Default rate limit applied to explorer requests, matching the Etherscan free tier.
+///
+/// Explorer limiters are shared by every chain using the same API key, see
+/// [`RateLimiter::for_api_key`].
+pub(crate) const DEFAULT_EXPLORER_RATE_LIMIT: RateLimit = RateLimit {
+    requests_per_second: 5,
+    burst: None,
+};
+
+/// Explorer rate limiters, keyed by API key and limit.
+static EXPLORER_LIMITERS: LazyLock<Mutex<HashMap<(String, RateLimit), RateLimiter>>> =
+    LazyLock::new(Default::default);
+
+/// Token-bucket rate limit configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RateLimit {
+    /// Sustained number of requests allowed per second
+    #[serde(rename = "requestsPerSecond")]
+    pub requests_per_second: u32,
+
+    /// Maximum number of requests that can be sent in a burst
+    ///
+    /// Defaults to `requests_per_second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+}
+
+/// A shared token-bucket rate limiter.
+///
+/// Clones share the same bucket, so a single limiter can throttle requests from many clients.
+#[derive(Clone, Debug)]
+pub struct RateLimiter(Arc<Mutex<Bucket>>);
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Constructs a new `RateLimiter` with a full bucket.
+    pub fn new(limit: RateLimit) -> Self {
+        let refill_per_sec = f64::from(limit.requests_per_second.max(1));
+        let capacity = f64::from(limit.burst.unwrap_or(limit.requests_per_second).max(1));
+
+        Self(Arc::new(Mutex::new(Bucket {
+            capacity,
+            tokens: capacity,
+            refill_per_sec,
+            last_refill: Instant::now(),
+        })))
+    }
+
+    /// Returns the explorer rate limiter shared by every client using `api_key` with `limit`.
+    ///
+    /// Chains configured with different limits for the same key get separate buckets.
+    pub fn for_api_key(api_key: &str, limit: RateLimit) -> Self {
+        EXPLORER_LIMITERS
+            .lock()
+            .expect("explorer limiter lock poisoned")
+            .entry((api_key.to_string(), limit))
+            .or_insert_with(|| Self::new(limit))
+            .clone()
+    }
+
+    /// Waits until a request may be sent, consuming one token from the bucket.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.0.lock().expect("rate limiter lock poisoned");
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens =
+                    (bucket.tokens + elapsed * bucket.refill_per_sec).min(bucket.capacity);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.refill_per_sec)
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::time::Instant;
+
+    use super::{RateLimit, RateLimiter};
+
+    /// Acquires `count` tokens from `limiter` and returns how long it took.
+    async fn time_acquire(limiter: &RateLimiter, count: usize) -> Duration {
+        let start = Instant::now();
+        for _ in 0..count {
+            limiter.acquire().await;
+        }
+        start.elapsed()
+    }
+
+    fn assert_close(elapsed: Duration, expected: Duration) {
+        let error = elapsed.abs_diff(expected);
+        assert!(
+            error < Duration::from_millis(10),
+            "took {elapsed:?}, expected {expected:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paces_requests_after_a_burst() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests_per_second: 2,
+            burst: Some(4),
+        });
+
+        assert_close(time_acquire(&limiter, 4).await, Duration::ZERO);
+        assert_close(time_acquire(&limiter, 4).await, Duration::from_secs(2));
+
+        // the bucket refills while idle, up to the burst
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_close(time_acquire(&limiter, 4).await, Duration::ZERO);
+        assert_close(time_acquire(&limiter, 1).await, Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn api_keys_share_a_limiter_per_limit() {
+        let limit = RateLimit {
+            requests_per_second: 1,
+            burst: None,
+        };
+        let first = RateLimiter::for_api_key("shared-key", limit);
+        let second = RateLimiter::for_api_key("shared-key", limit);
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+
+        assert_close(time_acquire(&first, 1).await, Duration::ZERO);
+        assert_close(time_acquire(&second, 1).await, Duration::from_secs(1));
+
+        // other keys and other limits get their own bucket
+        let other_key = RateLimiter::for_api_key("other-key", limit);
+        let other_limit = RateLimiter::for_api_key(
+            "shared-key",
+            RateLimit {
+                requests_per_second: 1,
+                burst: Some(2),
+            },
+        );
+        assert_close(time_acquire(&other_key, 1).await, Duration::ZERO);
+        assert_close(time_acquire(&other_limit, 1).await, Duration::ZERO);
+    }
+}
@@ -0,0 +1,757 @@
+//! Record and replay of RPC and explorer traffic.
+//!
+//! A [`Cassette`] is a JSON file of request/response pairs. [`RecordingBackend`] and
+//! [`RecordingSource`] wrap a live [`ChainBackend`] and [`TransactionSource`] and append every
+//! exchange to a cassette, while [`ReplayBackend`] and [`ReplaySource`] answer from a recorded
+//! cassette without network access. See [`ChainMetaData::create_recording_client`] and
+//! [`ChainMetaData::create_replay_client`].
+//!
+//! Requests are matched on their method (or explorer action) and parameters; JSON-RPC IDs are
+//! ignored. Identical requests are answered in recorded order, and the last recorded answer is
+//! repeated once they run out.
+//!
+//! [`ChainMetaData::create_recording_client`]: crate::ChainMetaData::create_recording_client
+//! [`ChainMetaData::create_replay_client`]: crate::ChainMetaData::create_replay_client
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use alloy::{
+    json_abi::JsonAbi,
+    primitives::{Address, U256},
+    rpc::json_rpc::{
+        ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+    },
+    transports::{TransportError, TransportErrorKind, TransportFut},
+};
+use foundry_block_explorers::{
+    account::{ERC20TokenTransferEvent, InternalTransaction, NormalTransaction, TxListParams},
+    errors::EtherscanError,
+};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, value::to_raw_value};
+use tracing::warn;
+
+use crate::{
+    backend::ChainBackend,
+    error::{ClientError, Result},
+    history::TransactionSource,
+};
+
+/// A recorded JSON-RPC exchange.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RpcEntry {
+    method: String,
+    params: Value,
+    #[serde(flatten)]
+    outcome: RpcOutcome,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum RpcOutcome {
+    Result(Value),
+    Error {
+        code: i64,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<Value>,
+    },
+    TransportError(String),
+}
+
+/// A recorded explorer exchange.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct ExplorerEntry {
+    #[serde(flatten)]
+    request: ExplorerRequest,
+    #[serde(flatten)]
+    outcome: ExplorerOutcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExplorerRequest {
+    action: String,
+    address: Address,
+    start_block: u64,
+    end_block: u64,
+    page: u64,
+    offset: u64,
+    sort: String,
+}
+
+impl ExplorerRequest {
+    fn new(action: &'static str, address: Address, params: TxListParams) -> Self {
+        Self {
+            action: action.to_string(),
+            address,
+            start_block: params.start_block,
+            end_block: params.end_block,
+            page: params.page,
+            offset: params.offset,
+            sort: params.sort.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ExplorerOutcome {
+    Result(Value),
+    Error(RecordedExplorerError),
+}
+
+/// The subset of [`ClientError`] an explorer request can be replayed with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+enum RecordedExplorerError {
+    NoTransactions,
+    RateLimited,
+    Unsupported(String),
+    Other(String),
+}
+
+impl From<&ClientError> for RecordedExplorerError {
+    fn from(err: &ClientError) -> Self {
+        match err {
+            ClientError::NoTransactions => Self::NoTransactions,
+            ClientError::ExplorerRateLimited => Self::RateLimited,
+            ClientError::Unsupported(message) => Self::Unsupported(message.clone()),
+            err => Self::Other(err.to_string()),
+        }
+    }
+}
+
+impl From<RecordedExplorerError> for ClientError {
+    fn from(err: RecordedExplorerError) -> Self {
+        match err {
+            RecordedExplorerError::NoTransactions => Self::NoTransactions,
+            RecordedExplorerError::RateLimited => Self::ExplorerRateLimited,
+            RecordedExplorerError::Unsupported(message) => Self::Unsupported(message),
+            RecordedExplorerError::Other(message) => {
+                Self::Explorer(EtherscanError::Unknown(message))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct CassetteData {
+    #[serde(default)]
+    rpc: Vec<RpcEntry>,
+    #[serde(default)]
+    explorer: Vec<ExplorerEntry>,
+}
+
+#[derive(Debug)]
+struct CassetteState {
+    path: PathBuf,
+    data: CassetteData,
+    /// Number of times each recorded entry key has been replayed
+    replayed: HashMap<String, usize>,
+}
+
+/// A file of recorded RPC and explorer exchanges.
+///
+/// Clones share the same recording. While recording, the file is rewritten after every
+/// exchange, so it is complete even if the process exits abruptly.
+#[derive(Clone, Debug)]
+pub struct Cassette(Arc<Mutex<CassetteState>>);
+
+impl Cassette {
+    /// Creates an empty cassette that will be written to `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self(Arc::new(Mutex::new(CassetteState {
+            path: path.as_ref().to_path_buf(),
+            data: CassetteData::default(),
+            replayed: HashMap::new(),
+        })))
+    }
+
+    /// Loads a recorded cassette from `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| ClientError::Config(format!("Failed to read {}: {e}", path.display())))?;
+        let data = serde_json::from_str(&data).map_err(|e| {
+            ClientError::Config(format!("Invalid cassette {}: {e}", path.display()))
+        })?;
+
+        Ok(Self(Arc::new(Mutex::new(CassetteState {
+            path: path.to_path_buf(),
+            data,
+            replayed: HashMap::new(),
+        }))))
+    }
+
+    /// Writes the cassette to its file.
+    pub fn save(&self) -> Result<()> {
+        Self::write(&self.state())
+    }
+
+    fn state(&self) -> MutexGuard<'_, CassetteState> {
+        self.0.lock().expect("cassette lock poisoned")
+    }
+
+    fn write(state: &CassetteState) -> Result<()> {
+        let json = serde_json::to_string_pretty(&state.data)?;
+        fs::write(&state.path, json).map_err(|e| {
+            ClientError::Config(format!("Failed to write {}: {e}", state.path.display()))
+        })
+    }
+
+    fn record_rpc(&self, entries: impl IntoIterator<Item = RpcEntry>) {
+        let mut state = self.state();
+        state.data.rpc.extend(entries);
+        if let Err(err) = Self::write(&state) {
+            warn!("Failed to save cassette: {err}");
+        }
+    }
+
+    fn record_explorer(&self, entry: ExplorerEntry) {
+        let mut state = self.state();
+        state.data.explorer.push(entry);
+        if let Err(err) = Self::write(&state) {
+            warn!("Failed to save cassette: {err}");
+        }
+    }
+
+    /// Returns the next recorded outcome for a JSON-RPC request.
+    fn replay_rpc(&self, method: &str, params: &Value) -> Option<RpcOutcome> {
+        let mut state = self.state();
+        let key = format!("rpc:{method}:{params}");
+        let matches: Vec<_> = state
+            .data
+            .rpc
+            .iter()
+            .filter(|entry| entry.method == method && &entry.params == params)
+            .map(|entry| entry.outcome.clone())
+            .collect();
+        next_match(&mut state.replayed, key, matches)
+    }
+
+    /// Returns the next recorded outcome for an explorer request.
+    fn replay_explorer(&self, request: &ExplorerRequest) -> Option<ExplorerOutcome> {
+        let mut state = self.state();
+        let key = format!(
+            "explorer:{}",
+            serde_json::to_string(request).unwrap_or_default()
+        );
+        let matches: Vec<_> = state
+            .data
+            .explorer
+            .iter()
+            .filter(|entry| &entry.request == request)
+            .map(|entry| entry.outcome.clone())
+            .collect();
+        next_match(&mut state.replayed, key, matches)
+    }
+}
+
+/// Picks the recorded answer for the next replay of `key`, repeating the last one once all
+/// have been used.
+fn next_match<T>(
+    replayed: &mut HashMap<String, usize>,
+    key: String,
+    mut matches: Vec<T>,
+) -> Option<T> {
+    if matches.is_empty() {
+        return None;
+    }
+    let count = replayed.entry(key).or_default();
+    let idx = (*count).min(matches.len() - 1);
+    *count += 1;
+    Some(matches.swap_remove(idx))
+}
+
+fn request_params(request: &SerializedRequest) -> Value {
+    request
+        .params()
+        .and_then(|params| serde_json::from_str(params.get()).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// A [`ChainBackend`] that forwards requests to `inner` and records every exchange.
+#[derive(Debug)]
+pub struct RecordingBackend<B> {
+    inner: B,
+    cassette: Cassette,
+}
+
+impl<B: ChainBackend> RecordingBackend<B> {
+    /// Constructs a new `RecordingBackend` writing to `cassette`.
+    pub fn new(inner: B, cassette: Cassette) -> Self {
+        Self { inner, cassette }
+    }
+}
+
+impl<B: ChainBackend> ChainBackend for RecordingBackend<B> {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        let cassette = self.cassette.clone();
+        let requests: Vec<_> = request
+            .requests()
+            .iter()
+            .map(|request| {
+                (
+                    request.id().clone(),
+                    request.method().to_string(),
+                    request_params(request),
+                )
+            })
+            .collect();
+        let response = self.inner.send(request);
+
+        Box::pin(async move {
+            let response = response.await;
+            let entries = requests.into_iter().map(|(id, method, params)| {
+                let outcome = match &response {
+                    Ok(packet) => match packet.responses().iter().find(|r| r.id == id) {
+                        Some(Response {
+                            payload: ResponsePayload::Success(result),
+                            ..
+                        }) => RpcOutcome::Result(
+                            serde_json::from_str(result.get()).unwrap_or(Value::Null),
+                        ),
+                        Some(Response {
+                            payload: ResponsePayload::Failure(err),
+                            ..
+                        }) => RpcOutcome::Error {
+                            code: err.code,
+                            message: err.message.to_string(),
+                            data: err
+                                .data
+                                .as_ref()
+                                .and_then(|data| serde_json::from_str(data.get()).ok()),
+                        },
+                        None => RpcOutcome::TransportError("Missing response".to_string()),
+                    },
+                    Err(err) => RpcOutcome::TransportError(err.to_string()),
+                };
+                RpcEntry {
+                    method,
+                    params,
+                    outcome,
+                }
+            });
+            cassette.record_rpc(entries);
+            response
+        })
+    }
+
+    fn fails_over(&self) -> bool {
+        self.inner.fails_over()
+    }
+}
+
+/// A [`ChainBackend`] that answers requests from a recorded [`Cassette`].
+///
+/// Requests without a recording fail with a transport error naming the request.
+#[derive(Debug)]
+pub struct ReplayBackend {
+    cassette: Cassette,
+}
+
+impl ReplayBackend {
+    /// Constructs a new `ReplayBackend` reading from `cassette`.
+    pub fn new(cassette: Cassette) -> Self {
+        Self { cassette }
+    }
+
+    fn response(&self, request: &SerializedRequest) -> Result<Response, TransportError> {
+        let method = request.method();
+        let params = request_params(request);
+        let payload = match self.cassette.replay_rpc(method, &params) {
+            Some(RpcOutcome::Result(result)) => {
+                ResponsePayload::Success(to_raw_value(&result).map_err(TransportErrorKind::custom)?)
+            }
+            Some(RpcOutcome::Error {
+                code,
+                message,
+                data,
+            }) => ResponsePayload::Failure(ErrorPayload {
+                code,
+                message: message.into(),
+                data: data
+                    .map(|data| to_raw_value(&data))
+                    .transpose()
+                    .map_err(TransportErrorKind::custom)?,
+            }),
+            Some(RpcOutcome::TransportError(message)) => {
+                return Err(TransportErrorKind::custom_str(&message));
+            }
+            None => {
+                return Err(TransportErrorKind::custom_str(&format!(
+                    "No recorded response for {method} {params}"
+                )));
+            }
+        };
+
+        Ok(Response {
+            id: request.id().clone(),
+            payload,
+        })
+    }
+}
+
+impl ChainBackend for ReplayBackend {
+    fn send(&self, request: RequestPacket) -> TransportFut<'static> {
+        let response = match request {
+            RequestPacket::Single(request) => self.response(&request).map(ResponsePacket::Single),
+            RequestPacket::Batch(requests) => requests
+                .iter()
+                .map(|request| self.response(request))
+                .collect::<Result<_, _>>()
+                .map(ResponsePacket::Batch),
+        };
+        Box::pin(async move { response })
+    }
+}
+
+/// A [`TransactionSource`] that forwards requests to `inner` and records every exchange.
+#[derive(Debug)]
+pub struct RecordingSource {
+    inner: Arc<dyn TransactionSource>,
+    cassette: Cassette,
+}
+
+impl RecordingSource {
+    /// Constructs a new `RecordingSource` writing to `cassette`.
+    pub fn new(inner: Arc<dyn TransactionSource>, cassette: Cassette) -> Self {
+        Self { inner, cassette }
+    }
+
+    async fn record<T: Serialize>(
+        &self,
+        request: ExplorerRequest,
+        response: BoxFuture<'_, Result<T>>,
+    ) -> Result<T> {
+        let response = response.await;
+        let outcome = match &response {
+            Ok(result) => {
+                let mut result = serde_json::to_value(result)?;
+                normalize_explorer_value(&mut result);
+                ExplorerOutcome::Result(result)
+            }
+            Err(err) => ExplorerOutcome::Error(err.into()),
+        };
+        self.cassette
+            .record_explorer(ExplorerEntry { request, outcome });
+        response
+    }
+}
+
+impl TransactionSource for RecordingSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+        let request = ExplorerRequest::new("txlist", address, params);
+        Box::pin(self.record(request, self.inner.transactions(address, params)))
+    }
+
+    fn internal_transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+        let request = ExplorerRequest::new("txlistinternal", address, params);
+        Box::pin(self.record(request, self.inner.internal_transactions(address, params)))
+    }
+
+    fn token_transfers(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+        let request = ExplorerRequest::new("tokentx", address, params);
+        Box::pin(self.record(request, self.inner.token_transfers(address, params)))
+    }
+
+    fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+        let request = ExplorerRequest::new("getabi", address, TxListParams::default());
+        Box::pin(self.record(request, self.inner.contract_abi(address)))
+    }
+}
+
+/// Fields of explorer items that are serialized as hex numbers but only deserialized from
+/// decimal strings.
+const NUMERIC_FIELDS: &[&str] = &[
+    "blockNumber",
+    "cumulativeGasUsed",
+    "gas",
+    "gasPrice",
+    "gasUsed",
+    "nonce",
+    "value",
+];
+
+/// Converts serialized explorer items back to the explorer's own format.
+///
+/// The explorer types are not round-trip safe: some fields are serialized as JSON-encoded
+/// strings (`"\"0x..\""`) and numbers as hex, but deserialized from plain strings and decimal
+/// numbers respectively.
+fn normalize_explorer_value(value: &mut Value) {
+    match value {
+        Value::String(string) if string.len() >= 2 && string.starts_with('"') => {
+            if let Ok(Value::String(inner)) = serde_json::from_str(string) {
+                *string = inner;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_explorer_value),
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                normalize_explorer_value(field);
+                if let Value::String(number) = field
+                    && NUMERIC_FIELDS.contains(&name.as_str())
+                    && let Ok(parsed) = number.parse::<U256>()
+                {
+                    *number = parsed.to_string();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A [`TransactionSource`] that answers requests from a recorded [`Cassette`].
+///
+/// Requests without a recording fail with [`ClientError::NotFound`].
+#[derive(Debug)]
+pub struct ReplaySource {
+    cassette: Cassette,
+}
+
+impl ReplaySource {
+    /// Constructs a new `ReplaySource` reading from `cassette`.
+    pub fn new(cassette: Cassette) -> Self {
+        Self { cassette }
+    }
+
+    fn replay<T>(&self, request: ExplorerRequest) -> BoxFuture<'_, Result<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let result = match self.cassette.replay_explorer(&request) {
+            Some(ExplorerOutcome::Result(result)) => {
+                serde_json::from_value(result).map_err(Into::into)
+            }
+            Some(ExplorerOutcome::Error(err)) => Err(err.into()),
+            None => Err(ClientError::NotFound(format!(
+                "No recorded explorer response for {} of {}, page {}",
+                request.action, request.address, request.page
+            ))),
+        };
+        Box::pin(async move { result })
+    }
+}
+
+impl TransactionSource for ReplaySource {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+        self.replay(ExplorerRequest::new("txlist", address, params))
+    }
+
+    fn internal_transactions(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+        self.replay(ExplorerRequest::new("txlistinternal", address, params))
+    }
+
+    fn token_transfers(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+        self.replay(ExplorerRequest::new("tokentx", address, params))
+    }
+
+    fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+        self.replay(ExplorerRequest::new(
+            "getabi",
+            address,
+            TxListParams::default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use alloy::{
+        json_abi::JsonAbi,
+        primitives::{Address, address},
+    };
+    use foundry_block_explorers::account::{
+        ERC20TokenTransferEvent, InternalTransaction, NormalTransaction, TxListParams,
+    };
+    use futures::future::BoxFuture;
+    use serde_json::{Value, json};
+
+    use super::{Cassette, RecordingBackend, RecordingSource};
+    use crate::{
+        EvmChainClient, ScriptedBackend, ScriptedResponse,
+        error::{ClientError, Result},
+        history::TransactionSource,
+        test_utils::{chain_metadata, eth_call_params, token, word},
+    };
+
+    const OWNER: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    /// A token whose `balanceOf` reverts
+    const BROKEN: Address = address!("0x000000000000000000000000000000000000dEaD");
+
+    /// A transaction source with a single transaction and no token transfers.
+    #[derive(Debug)]
+    struct ScriptedSource;
+
+    impl TransactionSource for ScriptedSource {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn transactions(
+            &self,
+            _address: Address,
+            params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<NormalTransaction>>> {
+            Box::pin(async move {
+                if params.page > 1 {
+                    return Err(ClientError::NoTransactions);
+                }
+                let transaction = json!({
+                    "blockNumber": "19000000",
+                    "timeStamp": "1705000000",
+                    "hash": "0x3d6f0e5a1b2c4d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9a0b1c2d3e4f50",
+                    "nonce": "42",
+                    "blockHash": "0x8c1f2e3d4c5b6a79881726354453627180f9e8d7c6b5a4938271605f4e3d2c1b",
+                    "transactionIndex": "7",
+                    "from": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+                    "to": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                    "value": "1500000000000000000",
+                    "gas": "52000",
+                    "gasPrice": "23000000000",
+                    "isError": "0",
+                    "txreceipt_status": "1",
+                    "input": "0x",
+                    "contractAddress": "",
+                    "cumulativeGasUsed": "1234567",
+                    "gasUsed": "21000",
+                    "confirmations": "100",
+                    "methodId": "0x",
+                    "functionName": ""
+                });
+                Ok(vec![serde_json::from_value(transaction)?])
+            })
+        }
+
+        fn internal_transactions(
+            &self,
+            _address: Address,
+            _params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<InternalTransaction>>> {
+            Box::pin(async { Err(ClientError::NoTransactions) })
+        }
+
+        fn token_transfers(
+            &self,
+            _address: Address,
+            _params: TxListParams,
+        ) -> BoxFuture<'_, Result<Vec<ERC20TokenTransferEvent>>> {
+            Box::pin(async { Ok(vec![]) })
+        }
+
+        fn contract_abi(&self, address: Address) -> BoxFuture<'_, Result<JsonAbi>> {
+            Box::pin(async move { Err(ClientError::NotFound(format!("ABI of {address}"))) })
+        }
+    }
+
+    fn scripted_backend() -> ScriptedBackend {
+        let backend = ScriptedBackend::new();
+        backend
+            .on("eth_getBalance", ScriptedResponse::result("0x10"))
+            .on_request("eth_call", |params: &Value| {
+                match eth_call_params(params).0 {
+                    USDC => ScriptedResponse::result(word(5)),
+                    _ => ScriptedResponse::error(3, "execution reverted"),
+                }
+            });
+        backend
+    }
+
+    /// Runs a session against `client`, returning everything it read.
+    async fn session(client: &EvmChainClient) -> Value {
+        let balance = client.get_native_balance(OWNER).await.unwrap();
+        let tokens: Vec<_> = client
+            .get_token_balance_results(OWNER)
+            .await
+            .into_iter()
+            .map(|result| match result.balance {
+                Ok(balance) => json!({ "token": result.token.symbol, "balance": balance }),
+                Err(err) => json!({ "token": result.token.symbol, "error": err.to_string() }),
+            })
+            .collect();
+        // a full page, so the next one is probed and found empty
+        let (transactions, has_more) = client.get_transactions(OWNER, 1, 1).await.unwrap();
+        let (transfers, _) = client.get_token_transfers(OWNER, 1, 10).await.unwrap();
+
+        json!({
+            "balance": balance,
+            "tokens": tokens,
+            "transactions": transactions,
+            "hasMore": has_more,
+            "transfers": transfers,
+        })
+    }
+
+    #[tokio::test]
+    async fn replays_a_recorded_session() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", std::process::id()));
+        let metadata = chain_metadata("test", 1);
+        let tokens = vec![token("USDC", USDC), token("BROKEN", BROKEN)];
+
+        let cassette = Cassette::new(&path);
+        let recording = EvmChainClient::with_backend(
+            metadata.clone(),
+            RecordingBackend::new(scripted_backend(), cassette.clone()),
+            Arc::new(RecordingSource::new(
+                Arc::new(ScriptedSource),
+                cassette.clone(),
+            )),
+            tokens.clone(),
+        );
+        let recorded = session(&recording).await;
+
+        let replay = metadata.create_replay_client(&Cassette::load(&path).unwrap(), tokens);
+        let replayed = session(&replay).await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            recorded["tokens"][1]["error"],
+            "Execution reverted: execution reverted"
+        );
+        assert_eq!(recorded["transactions"].as_array().unwrap().len(), 1);
+        assert_eq!(recorded["hasMore"], false);
+        assert_eq!(replayed, recorded);
+    }
+}
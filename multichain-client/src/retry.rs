@@ -0,0 +1,354 @@
+//! Retry with jittered exponential backoff for RPC and explorer requests.
+//!
+//! RPC requests are retried by [`RetryLayer`], a transport layer that also applies the chain's
+//! [`RateLimiter`]. Explorer requests are retried by [`RetryPolicy::retry`] directly.
+
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{RpcError, TransportError, TransportFut},
+};
+use foundry_block_explorers::errors::EtherscanError;
+use serde::{Deserialize, Serialize};
+use tower::{Layer, Service};
+use tracing::debug;
+
+use crate::rate_limit::RateLimiter;
+
+/// Retry policy for failed requests.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    #[serde(rename = "maxAttempts")]
+    pub max_attempts: u32,
+
+    /// Backoff before the first retry, in milliseconds
+    #[serde(rename = "initialBackoffMs")]
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound for the backoff between retries, in milliseconds
+    #[serde(rename = "maxBackoffMs")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff_ms: 250,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `op` until it succeeds, `should_retry` rejects its result, or the attempts run out.
+    ///
+    /// Returns the result of the last attempt.
+    pub async fn retry<T, E, F, Fut>(
+        &self,
+        mut op: F,
+        should_retry: impl Fn(&Result<T, E>) -> bool,
+    ) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let result = op().await;
+            if attempt >= self.max_attempts || !should_retry(&result) {
+                return result;
+            }
+
+            let delay = self.backoff(attempt);
+            debug!(
+                "Retrying request in {delay:?} (attempt {attempt}/{})",
+                self.max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Returns the jittered backoff before retry number `attempt`.
+    ///
+    /// The delay doubles with every attempt up to `max_backoff_ms`, and a random value between
+    /// half and all of it is used to spread out retries from concurrent requests.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .initial_backoff_ms
+            .saturating_mul(1 << (attempt - 1).min(20))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(rand::random_range(exp / 2..=exp))
+    }
+}
+
+/// Returns `true` if a failed explorer request is worth retrying.
+pub(crate) fn is_retryable_explorer_error<T>(result: &Result<T, EtherscanError>) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(EtherscanError::RateLimitExceeded | EtherscanError::Reqwest(_)) => true,
+        Err(EtherscanError::ErrorResponse {
+            message, result, ..
+        }) => [Some(message), result.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|msg| msg.to_lowercase().contains("rate limit")),
+        Err(_) => false,
+    }
+}
+
+/// Returns `true` if an RPC request was rate limited, or failed at the transport level and
+/// `transport_errors` are retried.
+fn is_retryable_rpc_result(
+    result: &Result<ResponsePacket, TransportError>,
+    transport_errors: bool,
+) -> bool {
+    match result {
+        Ok(response) => response.iter_errors().any(|err| err.is_retry_err()),
+        Err(RpcError::Transport(_)) => transport_errors,
+        Err(_) => false,
+    }
+}
+
+/// A transport layer that rate limits RPC requests and retries them according to a
+/// [`RetryPolicy`].
+///
+/// Rate limited requests and transport errors are retried. Transport errors can be left to a
+/// backend that fails over between endpoints with [`Self::with_transport_retries`].
+#[derive(Clone, Debug)]
+pub struct RetryLayer {
+    policy: RetryPolicy,
+    limiter: Option<RateLimiter>,
+    transport_retries: bool,
+}
+
+impl RetryLayer {
+    /// Constructs a new `RetryLayer`.
+    pub fn new(policy: RetryPolicy, limiter: Option<RateLimiter>) -> Self {
+        Self {
+            policy,
+            limiter,
+            transport_retries: true,
+        }
+    }
+
+    /// Sets whether requests failing at the transport level are retried.
+    pub fn with_transport_retries(mut self, enabled: bool) -> Self {
+        self.transport_retries = enabled;
+        self
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = RetryService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RetryService {
+            inner,
+            policy: self.policy,
+            limiter: self.limiter.clone(),
+            transport_retries: self.transport_retries,
+        }
+    }
+}
+
+/// The transport service created by [`RetryLayer`].
+#[derive(Clone, Debug)]
+pub struct RetryService<S> {
+    inner: S,
+    policy: RetryPolicy,
+    limiter: Option<RateLimiter>,
+    transport_retries: bool,
+}
+
+impl<S> Service<RequestPacket> for RetryService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            this.policy
+                .retry(
+                    || {
+                        let mut inner = this.inner.clone();
+                        let limiter = this.limiter.clone();
+                        let request = request.clone();
+                        async move {
+                            if let Some(limiter) = limiter {
+                                limiter.acquire().await;
+                            }
+                            inner.call(request).await
+                        }
+                    },
+                    |result| is_retryable_rpc_result(result, this.transport_retries),
+                )
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use alloy::{
+        primitives::Address,
+        rpc::json_rpc::{ErrorPayload, Id, Response, ResponsePacket, ResponsePayload},
+        transports::TransportErrorKind,
+    };
+    use foundry_block_explorers::errors::EtherscanError;
+
+    use super::{RetryPolicy, is_retryable_explorer_error, is_retryable_rpc_result};
+    use crate::{
+        ClientError, ScriptedBackend, ScriptedResponse,
+        test_utils::{chain_metadata, scripted_client},
+    };
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 4,
+        initial_backoff_ms: 100,
+        max_backoff_ms: 1_000,
+    };
+
+    /// Runs [`POLICY`] over an operation failing `failures` times, and returns its result and
+    /// the number of attempts.
+    async fn run(failures: u32, retryable: bool) -> (Result<u32, u32>, u32) {
+        let attempts = AtomicU32::new(0);
+        let result = POLICY
+            .retry(
+                || async {
+                    let attempt = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                    if attempt > failures {
+                        Ok(attempt)
+                    } else {
+                        Err(attempt)
+                    }
+                },
+                |result| result.is_err() && retryable,
+            )
+            .await;
+        (result, attempts.load(Ordering::Relaxed))
+    }
+
+    fn error_response(code: i64, message: &'static str) -> ResponsePacket {
+        ResponsePacket::Single(Response {
+            id: Id::Number(1),
+            payload: ResponsePayload::Failure(ErrorPayload {
+                code,
+                message: message.into(),
+                data: None,
+            }),
+        })
+    }
+
+    fn explorer_error(message: &str, result: Option<&str>) -> Result<(), EtherscanError> {
+        Err(EtherscanError::ErrorResponse {
+            status: "0".to_string(),
+            message: message.to_string(),
+            result: result.map(str::to_string),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_until_success() {
+        assert_eq!(run(2, true).await, (Ok(3), 3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_after_max_attempts() {
+        assert_eq!(run(10, true).await, (Err(4), 4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn does_not_retry_other_failures() {
+        assert_eq!(run(10, false).await, (Err(1), 1));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        for attempt in 1..=40 {
+            let ceiling = (100u64 << (attempt - 1).min(20)).min(1_000);
+            for _ in 0..20 {
+                let delay = POLICY.backoff(attempt);
+                assert!(
+                    delay >= Duration::from_millis(ceiling / 2)
+                        && delay <= Duration::from_millis(ceiling),
+                    "attempt {attempt} waited {delay:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn classifies_explorer_errors() {
+        assert!(!is_retryable_explorer_error(&Ok(())));
+        assert!(is_retryable_explorer_error::<()>(&Err(
+            EtherscanError::RateLimitExceeded
+        )));
+        assert!(is_retryable_explorer_error(&explorer_error(
+            "NOTOK",
+            Some("Max rate limit reached")
+        )));
+        assert!(!is_retryable_explorer_error(&explorer_error(
+            "NOTOK",
+            Some("Invalid API Key")
+        )));
+        assert!(!is_retryable_explorer_error::<()>(&Err(
+            EtherscanError::ContractCodeNotVerified(Address::ZERO)
+        )));
+    }
+
+    #[test]
+    fn classifies_rpc_results() {
+        let transport = || Err(TransportErrorKind::custom_str("connection refused"));
+        assert!(is_retryable_rpc_result(&transport(), true));
+        assert!(!is_retryable_rpc_result(&transport(), false));
+
+        let rate_limited = Ok(error_response(429, "Too many requests"));
+        assert!(is_retryable_rpc_result(&rate_limited, false));
+        let reverted = Ok(error_response(3, "execution reverted"));
+        assert!(!is_retryable_rpc_result(&reverted, true));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clients_retry_transport_errors() {
+        let backend = ScriptedBackend::new();
+        backend.on(
+            "eth_blockNumber",
+            ScriptedResponse::TransportError("connection refused".to_string()),
+        );
+        let mut metadata = chain_metadata("test", 1);
+        metadata.retry = Some(POLICY);
+        let client = scripted_client(metadata, &backend, vec![]);
+
+        let err = client.get_block_number().await.unwrap_err();
+        assert!(matches!(err, ClientError::Transport(_)));
+        assert_eq!(backend.requests().len(), 4);
+    }
+}
@@ -0,0 +1,249 @@
+//! Reconnecting `eth_subscribe` streams over WebSocket endpoints.
+//!
+//! [`Subscriptions`] subscribes on the chain's `ws://` and `wss://` endpoints. A dropped
+//! connection is re-established on the same endpoint, re-creating its subscriptions; when that
+//! fails, the stream subscribes again on the next endpoint with an exponential backoff, so
+//! consumers see a single uninterrupted stream. Items announced while disconnected are not
+//! replayed.
+
+use std::{sync::Arc, time::Duration};
+
+use alloy::{
+    primitives::{B256, U64, U128},
+    pubsub::{PubSubFrontend, Subscription},
+    rpc::types::{Filter, Log},
+    transports::http::reqwest::Url,
+};
+use futures::{Stream, StreamExt, stream};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use crate::{
+    backend::{WsBackend, WsConnection},
+    error::{ClientError, Result},
+    metadata::BlockHead,
+};
+
+/// Delay before the first attempt to subscribe again after a failure.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between attempts to subscribe again.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The subset of a `newHeads` notification needed for [`BlockHead`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcHead {
+    number: U64,
+    hash: B256,
+    timestamp: U64,
+    #[serde(default)]
+    base_fee_per_gas: Option<U128>,
+}
+
+impl From<RpcHead> for BlockHead {
+    fn from(head: RpcHead) -> Self {
+        Self {
+            number: head.number.to(),
+            hash: head.hash,
+            timestamp: head.timestamp.to(),
+            base_fee_per_gas: head.base_fee_per_gas.map(|fee| fee.to()),
+        }
+    }
+}
+
+/// Opens `eth_subscribe` streams on the WebSocket endpoints of one chain, failing over between
+/// them.
+///
+/// Obtained from [`EvmChainClient::subscriptions`](crate::EvmChainClient::subscriptions) on
+/// clients whose chain lists a WebSocket RPC URL.
+#[derive(Clone, Debug)]
+pub struct Subscriptions {
+    chain: Arc<str>,
+    endpoints: Arc<[WsBackend]>,
+}
+
+impl Subscriptions {
+    /// Constructs a new `Subscriptions` for `chain` from a non-empty list of WebSocket endpoint
+    /// URLs.
+    pub fn new(chain: &str, urls: Vec<Url>) -> Result<Self> {
+        Self::with_backends(
+            chain,
+            urls.iter()
+                .map(|url| WsBackend::new(url.as_str()))
+                .collect(),
+        )
+    }
+
+    /// Constructs a new `Subscriptions` for `chain` from a non-empty list of WebSocket
+    /// backends, whose connections can be shared with an
+    /// [`EndpointPool`](crate::EndpointPool).
+    pub fn with_backends(chain: &str, endpoints: Vec<WsBackend>) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(ClientError::Config(format!(
+                "No WebSocket RPC endpoints for chain: {chain}"
+            )));
+        }
+
+        Ok(Self {
+            chain: chain.into(),
+            endpoints: endpoints.into(),
+        })
+    }
+
+    /// Returns the URLs of all endpoints, in failover order.
+    pub fn endpoints(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.iter().map(WsBackend::url)
+    }
+
+    /// Streams the header of every new block.
+    ///
+    /// After a reorg, the headers of the new canonical blocks are streamed again.
+    pub fn new_heads(&self) -> impl Stream<Item = BlockHead> + Send + 'static {
+        self.subscribe::<RpcHead>(json!(["newHeads"]))
+            .map(BlockHead::from)
+    }
+
+    /// Streams the logs matching `filter` as new blocks are mined.
+    ///
+    /// Only the filter's addresses and topics are used. Logs of blocks removed by a reorg are
+    /// streamed again with `removed` set.
+    pub fn logs(&self, filter: Filter) -> impl Stream<Item = Log> + Send + 'static {
+        let mut params = json!(filter);
+        if let Some(params) = params.as_object_mut() {
+            params.retain(|key, _| key == "address" || key == "topics");
+        }
+        self.subscribe(json!(["logs", params]))
+    }
+
+    /// Streams the notifications of an `eth_subscribe` call with `params`, subscribing again
+    /// whenever the subscription is lost.
+    fn subscribe<T>(&self, params: Value) -> impl Stream<Item = T> + Send + 'static
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let state = SubscriptionState {
+            subscriptions: self.clone(),
+            params,
+            active: None,
+            endpoint: 0,
+            failures: 0,
+        };
+        stream::unfold(state, |mut state| async move {
+            let item = state.next().await;
+            Some((item, state))
+        })
+    }
+}
+
+/// A live subscription, cancelled on the endpoint when dropped.
+struct ActiveSubscription<T> {
+    frontend: PubSubFrontend,
+    id: B256,
+    subscription: Subscription<T>,
+}
+
+impl<T> Drop for ActiveSubscription<T> {
+    fn drop(&mut self) {
+        let _ = self.frontend.unsubscribe(self.id);
+    }
+}
+
+/// State of a stream returned by [`Subscriptions::subscribe`].
+struct SubscriptionState<T> {
+    subscriptions: Subscriptions,
+    params: Value,
+    active: Option<ActiveSubscription<T>>,
+    /// Index of the endpoint subscribed to, or to subscribe to next
+    endpoint: usize,
+    /// Consecutive failures to subscribe, or losses of the subscription
+    failures: u32,
+}
+
+impl<T: DeserializeOwned> SubscriptionState<T> {
+    /// Waits for the next notification, subscribing again as many times as needed.
+    async fn next(&mut self) -> T {
+        loop {
+            if let Some(active) = &mut self.active {
+                match active.subscription.recv().await {
+                    Ok(item) => {
+                        self.failures = 0;
+                        return item;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            chain = %self.subscriptions.chain,
+                            endpoint = %self.endpoint().url(),
+                            "Subscription lagged, skipped {skipped} notifications"
+                        );
+                        continue;
+                    }
+                    Err(RecvError::Closed) => {
+                        warn!(
+                            chain = %self.subscriptions.chain,
+                            endpoint = %self.endpoint().url(),
+                            "Subscription lost, subscribing again"
+                        );
+                        self.active = None;
+                        self.endpoint().disconnect().await;
+                        self.fail_over();
+                    }
+                }
+            }
+
+            if self.failures > 0 {
+                tokio::time::sleep(self.backoff()).await;
+            }
+            match Self::subscribe(self.endpoint().clone(), self.params.clone()).await {
+                Ok(active) => {
+                    debug!(
+                        chain = %self.subscriptions.chain,
+                        endpoint = %self.endpoint().url(),
+                        "Subscribed to {}",
+                        self.params
+                    );
+                    self.active = Some(active);
+                }
+                Err(err) => {
+                    warn!(
+                        chain = %self.subscriptions.chain,
+                        endpoint = %self.endpoint().url(),
+                        "Failed to subscribe: {err}"
+                    );
+                    self.fail_over();
+                }
+            }
+        }
+    }
+
+    /// Sends `eth_subscribe` with `params` to `endpoint`.
+    async fn subscribe(endpoint: WsBackend, params: Value) -> Result<ActiveSubscription<T>> {
+        let WsConnection { frontend, client } = endpoint.connection().await?;
+        let id = client.request::<_, B256>("eth_subscribe", params).await?;
+        let subscription = frontend.get_subscription(id).await?.into_typed();
+        Ok(ActiveSubscription {
+            frontend,
+            id,
+            subscription,
+        })
+    }
+
+    fn endpoint(&self) -> &WsBackend {
+        &self.subscriptions.endpoints[self.endpoint]
+    }
+
+    /// Records a failure and moves on to the next endpoint.
+    fn fail_over(&mut self) {
+        self.failures += 1;
+        self.endpoint = (self.endpoint + 1) % self.subscriptions.endpoints.len();
+    }
+
+    /// Returns the delay before subscribing again, doubling with every consecutive failure.
+    fn backoff(&self) -> Duration {
+        INITIAL_BACKOFF
+            .saturating_mul(1 << (self.failures - 1).min(6))
+            .min(MAX_BACKOFF)
+    }
+}
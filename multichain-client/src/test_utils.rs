@@ -0,0 +1,77 @@
+//! Helpers shared by the unit tests of this crate.
+
+use std::sync::Arc;
+
+use alloy::primitives::{Address, Bytes, U256};
+use serde_json::Value;
+
+use crate::{
+    EvmChainClient, NoTransactionSource, ScriptedBackend,
+    history::ExplorerConfig,
+    metadata::{ChainMetaData, NativeCurrency, TokenMetadata},
+    retry::RetryPolicy,
+};
+
+/// Returns the metadata of a test chain without RPC URLs, explorer or retries.
+pub(crate) fn chain_metadata(short_name: &str, chain_id: u64) -> ChainMetaData {
+    ChainMetaData {
+        name: format!("Test chain {short_name}"),
+        chain_id,
+        short_name: short_name.to_string(),
+        network_id: chain_id,
+        native_currency: NativeCurrency {
+            name: "Ether".to_string(),
+            symbol: "ETH".to_string(),
+            decimals: 18,
+        },
+        rpc: vec![],
+        multicall: None,
+        max_batch_size: None,
+        max_log_range: None,
+        max_log_concurrency: None,
+        // a single attempt, so scripted failures surface immediately
+        retry: Some(RetryPolicy {
+            max_attempts: 1,
+            initial_backoff_ms: 0,
+            max_backoff_ms: 0,
+        }),
+        rate_limit: None,
+        explorer_rate_limit: None,
+        explorer: Some(ExplorerConfig::None),
+        ens_registry: None,
+        l2_fee_model: None,
+    }
+}
+
+/// Constructs a client for `metadata` answering from `backend`, without a transaction source.
+pub(crate) fn scripted_client(
+    metadata: ChainMetaData,
+    backend: &ScriptedBackend,
+    tokens: Vec<TokenMetadata>,
+) -> EvmChainClient {
+    let source = Arc::new(NoTransactionSource::new(&metadata.short_name));
+    EvmChainClient::with_backend(metadata, backend.clone(), source, tokens)
+}
+
+/// Returns the metadata of an 18-decimals test token.
+pub(crate) fn token(symbol: &str, address: Address) -> TokenMetadata {
+    TokenMetadata {
+        name: symbol.to_string(),
+        address,
+        symbol: symbol.to_string(),
+        decimals: 18,
+    }
+}
+
+/// Returns the target and calldata of an `eth_call` request from its params.
+pub(crate) fn eth_call_params(params: &Value) -> (Address, Bytes) {
+    let call = &params[0];
+    let to = serde_json::from_value(call["to"].clone()).expect("eth_call without target");
+    let data = serde_json::from_value(call["data"].clone()).expect("eth_call without data");
+    (to, data)
+}
+
+/// ABI-encodes `value` as a 32-byte word, as returned by `eth_call` for a `uint256`.
+pub(crate) fn word(value: u64) -> Bytes {
+    Bytes::from(U256::from(value).to_be_bytes::<32>())
+}
@@ -9,4 +9,8 @@ pub struct Cli {
     pub token_folder: PathBuf,
     #[clap(long, default_value = "config/chains.json")]
     pub chains: PathBuf,
+    #[clap(long, default_value = "config/signatures.json")]
+    pub signatures: PathBuf,
+    #[clap(long, default_value = "cache/abis/")]
+    pub abi_cache: PathBuf,
 }
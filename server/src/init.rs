@@ -2,14 +2,17 @@ use std::{
     collections::HashMap,
     fs,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::{Router, routing::get};
 use mongodb::Client as MongoClient;
-use multichain_client::{ChainMetaData, EvmClientRegistry};
+use multichain_client::{
+    AbiCache, ChainMetaData, EvmClientRegistry, ExplorerConfig, SignatureDatabase,
+};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, level_filters::LevelFilter};
+use tracing::{info, level_filters::LevelFilter, warn};
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{AppState, root, routes::routes};
@@ -17,17 +20,46 @@ use crate::{AppState, root, routes::routes};
 pub async fn init_app_state<P: AsRef<Path>>(
     chain_list: P,
     token_folder: PathBuf,
+    signatures: PathBuf,
+    abi_cache: PathBuf,
 ) -> Result<AppState> {
     // setup registry
-    let etherscan_api_key = dotenvy::var("ETHERSCAN_API_KEY")?;
     let chains = read_chains_from_json(chain_list)?;
-    let registry = create_registry(chains, token_folder, &etherscan_api_key)?;
+    // only chains reading their history from Etherscan need a key
+    let etherscan_api_key = if chains
+        .iter()
+        .any(|chain| matches!(chain.explorer, None | Some(ExplorerConfig::Etherscan)))
+    {
+        dotenvy::var("ETHERSCAN_API_KEY").context("ETHERSCAN_API_KEY is required by Etherscan")?
+    } else {
+        String::new()
+    };
+    let abi_cache = AbiCache::new(abi_cache);
+    let registry = create_registry(chains, token_folder, &abi_cache, &etherscan_api_key)?;
 
     // setup mongodb
-    let uri = dotenvy::var("MONGODB_URI")?;
-    let mongodb = MongoClient::with_uri_str(uri).await?;
+    let mongodb = match dotenvy::var("MONGODB_URI") {
+        Ok(uri) => Some(MongoClient::with_uri_str(uri).await?),
+        Err(_) => {
+            warn!("MONGODB_URI is not set, fetched transactions will not be stored");
+            None
+        }
+    };
 
-    Ok(AppState { registry, mongodb })
+    // setup calldata decoding
+    let signatures = load_signatures(&signatures);
+    let abis = abi_cache.abis();
+    for (chain_id, address, abi) in &abis {
+        signatures.add_abi(*chain_id, *address, abi);
+    }
+    info!("✅ Loaded {} cached contract ABIs", abis.len());
+    let signatures = Arc::new(signatures);
+
+    Ok(AppState {
+        registry,
+        mongodb,
+        signatures,
+    })
 }
 
 pub fn init_tracing() -> Result<()> {
@@ -62,26 +94,54 @@ fn read_chains_from_json<P: AsRef<Path>>(path: P) -> Result<Vec<ChainMetaData>>
     Ok(serde_json::from_str(&data)?)
 }
 
+fn load_signatures(path: &Path) -> SignatureDatabase {
+    match SignatureDatabase::from_file(path) {
+        Ok(signatures) => {
+            info!("✅ Loaded {} function selectors", signatures.len());
+            signatures
+        }
+        Err(err) => {
+            warn!("Calldata decoding disabled: {err}");
+            SignatureDatabase::new()
+        }
+    }
+}
+
 fn create_registry(
     chains: Vec<ChainMetaData>,
     token_folder: PathBuf,
+    abi_cache: &AbiCache,
     etherscan_api_key: &str,
 ) -> Result<EvmClientRegistry> {
     let mut client_map = HashMap::new();
     for chain in chains.iter() {
-        if let Ok(mut client) = chain.create_rpc_client(etherscan_api_key) {
-            info!("✅ Created client for {}", chain.name);
-            let path = token_folder.join(format!("{}-tokens.json", chain.short_name));
-            if path.exists() {
-                client.add_tokens_from_file(path)?;
-                info!(
-                    "✅ Added {} tokens for {}",
-                    client.tokens().len(),
-                    chain.name
-                );
+        let client = match chain.create_rpc_client(etherscan_api_key) {
+            Ok(client) => client.with_abi_cache(abi_cache.clone()),
+            Err(err) => {
+                warn!("❌ Skipping {}: {err}", chain.name);
+                continue;
             }
-            client_map.insert(chain.short_name.clone(), client);
+        };
+        info!("✅ Created client for {}", chain.name);
+        let path = token_folder.join(format!("{}-tokens.json", chain.short_name));
+        if path.exists() {
+            client.add_tokens_from_file(path)?;
+            info!(
+                "✅ Added {} tokens for {}",
+                client.tokens().len(),
+                chain.name
+            );
+        }
+        let path = token_folder.join(format!("{}-nfts.json", chain.short_name));
+        if path.exists() {
+            client.add_nft_collections_from_file(path)?;
+            info!(
+                "✅ Added {} NFT collections for {}",
+                client.nft_collections().len(),
+                chain.name
+            );
         }
+        client_map.insert(chain.short_name.clone(), client);
     }
 
     Ok(EvmClientRegistry::new(client_map))
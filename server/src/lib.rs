@@ -0,0 +1,27 @@
+//! The Scanza HTTP API, serving the chains of an [`EvmClientRegistry`].
+//!
+//! The `server` binary builds an [`AppState`] from the configuration files with
+//! [`init::init_app_state`] and serves [`init::init_router`]; tests can build the state from a
+//! registry directly.
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+use std::sync::Arc;
+
+use mongodb::Client as MongoClient;
+use multichain_client::{EvmClientRegistry, SignatureDatabase};
+
+pub mod cli;
+pub mod init;
+mod routes;
+
+async fn root() -> &'static str {
+    "Welcome to Scanza"
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub registry: EvmClientRegistry,
+    /// Stores fetched transactions; they are only served, not stored, without a database
+    pub mongodb: Option<MongoClient>,
+    pub signatures: Arc<SignatureDatabase>,
+}
@@ -1,31 +1,13 @@
-#![warn(unused_crate_dependencies)]
 use std::net::SocketAddr;
 
 use anyhow::Result;
 use clap::Parser;
-use mongodb::Client as MongoClient;
-use multichain_client::EvmClientRegistry;
-use tokio::net::TcpListener;
-use tracing::info;
-
-use crate::{
+use server::{
     cli::Cli,
     init::{init_app_state, init_router, init_tracing},
 };
-
-mod cli;
-mod init;
-mod routes;
-
-async fn root() -> &'static str {
-    "Welcome to Scanza"
-}
-
-#[derive(Clone)]
-pub struct AppState {
-    pub registry: EvmClientRegistry,
-    pub mongodb: MongoClient,
-}
+use tokio::net::TcpListener;
+use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -38,7 +20,7 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // initialize app state
-    let state = init_app_state(cli.chains, cli.token_folder).await?;
+    let state = init_app_state(cli.chains, cli.token_folder, cli.signatures, cli.abi_cache).await?;
 
     // initialize router
     let app = init_router(state)?;
@@ -1,19 +1,128 @@
-use axum::{Json, Router, extract::State, routing::get};
-use multichain_client::ChainMetaData;
+use alloy::{eips::BlockId, primitives::Address};
+use axum::{
+    Json, Router,
+    extract::{FromRequestParts, Path, State},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use multichain_client::{ChainMetaData, ClientError};
+use serde::{Deserialize, Deserializer, de};
+use serde_json::{Value, json};
+use tracing::{debug, warn};
 
 use crate::{
     AppState,
     routes::{
-        balance::get_balance, tokens::get_tokens, transactions::get_transactions,
+        balance::get_balance,
+        internal_transactions::get_internal_transactions,
+        logs::get_logs,
+        nfts::get_nfts,
+        subscribe::{subscribe_heads, subscribe_logs},
+        token_transfers::get_token_transfers,
+        tokens::{add_token, get_tokens},
+        transactions::get_transactions,
+        tx::get_transaction,
         wallet::get_wallet,
     },
 };
 
 mod balance;
+mod internal_transactions;
+mod logs;
+mod nfts;
+mod subscribe;
+mod token_transfers;
 mod tokens;
 mod transactions;
+mod tx;
 mod wallet;
 
+/// Query parameters for routes that can read state at a past block.
+///
+/// `block` accepts a block number (decimal or `0x`-prefixed), a block hash, or one of the
+/// `latest`, `safe`, `finalized`, `earliest` and `pending` tags.
+#[derive(Debug, Deserialize)]
+pub struct BlockQuery {
+    #[serde(default, deserialize_with = "deserialize_block")]
+    pub block: Option<BlockId>,
+}
+
+/// Deserializes an optional `block` query parameter, accepting decimal block numbers in addition
+/// to the formats understood by [`BlockId`].
+pub fn deserialize_block<'de, D>(deserializer: D) -> Result<Option<BlockId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(block) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match block.parse::<u64>() {
+        Ok(number) => Ok(Some(BlockId::number(number))),
+        Err(_) => serde_json::from_value(Value::String(block))
+            .map(Some)
+            .map_err(de::Error::custom),
+    }
+}
+
+/// The `{chain}` and `{address}` segments of a route path.
+///
+/// `{address}` may be a hex address or an ENS name (e.g., `vitalik.eth`), which is resolved
+/// through [`EvmClientRegistry::resolve_name`](multichain_client::EvmClientRegistry::resolve_name).
+/// Segments that are neither a valid address nor a dotted name are rejected with 400.
+#[derive(Debug)]
+pub struct AddressPath {
+    pub chain: String,
+    pub address: Address,
+}
+
+impl FromRequestParts<AppState> for AddressPath {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Response> {
+        let rejection = match Path::<(String, Address)>::from_request_parts(parts, state).await {
+            Ok(Path((chain, address))) => return Ok(Self { chain, address }),
+            Err(rejection) => rejection,
+        };
+
+        // only segments that look like names are resolved, so malformed addresses are still
+        // rejected as such
+        let Path((chain, name)) = Path::<(String, String)>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        if !name.contains('.') {
+            return Err(rejection.into_response());
+        }
+
+        let address = match state.registry.resolve_name(&chain, &name).await {
+            Ok(address) => {
+                debug!("Resolved {name} to {address}");
+                address
+            }
+            Err(err) => {
+                warn!("Failed to resolve {name}: {err}");
+                return Err(client_error_response("Failed to resolve ENS name", &err));
+            }
+        };
+
+        Ok(Self { chain, address })
+    }
+}
+
+/// Looks up the verified primary ENS name of `address`, for inclusion in responses.
+///
+/// Lookups are best effort: failures are logged and reported as no name.
+pub async fn primary_name(state: &AppState, chain: &str, address: Address) -> Option<String> {
+    match state.registry.lookup_address(chain, address).await {
+        Ok(name) => name,
+        Err(ClientError::Unsupported(_)) => None,
+        Err(err) => {
+            debug!("Failed to look up the ENS name of {address}: {err}");
+            None
+        }
+    }
+}
+
 /// GET /chains — Returns list of loaded chains
 pub async fn get_chains(State(state): State<AppState>) -> Json<Vec<ChainMetaData>> {
     let chains = state
@@ -26,15 +135,59 @@ pub async fn get_chains(State(state): State<AppState>) -> Json<Vec<ChainMetaData
     Json(chains)
 }
 
+/// Builds the error response for a failed client call, choosing the status code from the
+/// kind of failure.
+pub fn client_error_response(error: &str, err: &ClientError) -> Response {
+    let status = match err {
+        ClientError::Transport(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ClientError::Rpc { .. } | ClientError::Explorer(_) | ClientError::Decode(_) => {
+            StatusCode::BAD_GATEWAY
+        }
+        ClientError::Reverted(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        ClientError::RpcRateLimited(_) | ClientError::ExplorerRateLimited => {
+            StatusCode::TOO_MANY_REQUESTS
+        }
+        ClientError::NoTransactions | ClientError::NotFound(_) => StatusCode::NOT_FOUND,
+        ClientError::InvalidName(_) => StatusCode::BAD_REQUEST,
+        ClientError::InvalidToken(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        ClientError::LimitExceeded(_) => StatusCode::CONFLICT,
+        ClientError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+        ClientError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (
+        status,
+        Json(json!({
+            "error": error,
+            "details": err.to_string()
+        })),
+    )
+        .into_response()
+}
+
 pub fn routes(state: AppState) -> Router {
     Router::new()
         .route("/chains", get(get_chains))
+        .route("/{chain}/tokens/{address}", post(add_token))
         .route("/{chain}/wallet/{address}/balance", get(get_balance))
         .route("/{chain}/wallet/{address}/tokens", get(get_tokens))
+        .route("/{chain}/wallet/{address}/nfts", get(get_nfts))
         .route(
             "/{chain}/wallet/{address}/transactions",
             get(get_transactions),
         )
+        .route(
+            "/{chain}/wallet/{address}/internal-transactions",
+            get(get_internal_transactions),
+        )
+        .route(
+            "/{chain}/wallet/{address}/token-transfers",
+            get(get_token_transfers),
+        )
         .route("/{chain}/wallet/{address}", get(get_wallet))
+        .route("/{chain}/address/{address}/logs", get(get_logs))
+        .route("/{chain}/tx/{hash}", get(get_transaction))
+        .route("/{chain}/subscribe/heads", get(subscribe_heads))
+        .route("/{chain}/subscribe/logs/{address}", get(subscribe_logs))
         .with_state(state)
 }
@@ -1,43 +1,53 @@
-use alloy::primitives::{Address, U256};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 use serde::Serialize;
 use tracing::{error, info, warn};
 
-use crate::AppState;
+use crate::{
+    AppState,
+    routes::{AddressPath, BlockQuery, client_error_response, primary_name},
+};
 
 #[derive(Serialize)]
 pub struct WalletBalanceResponse {
     pub address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub balance: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockId>,
 }
 
 pub async fn get_balance(
-    Path((chain, address)): Path<(String, Address)>,
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<BlockQuery>,
     State(state): State<AppState>,
 ) -> Response {
     info!("Getting natvie balance for {address} on {chain}");
+    let block = params.block.unwrap_or_default();
 
     match state.registry.get(&chain) {
-        Some(client) => match client.get_native_balance(address).await {
+        Some(client) => match client.get_native_balance_at(address, block).await {
             Ok(balance) => {
-                let response = WalletBalanceResponse { address, balance };
+                let response = WalletBalanceResponse {
+                    address,
+                    name: primary_name(&state, &chain, address).await,
+                    balance,
+                    block: params.block,
+                };
                 (StatusCode::OK, Json(response)).into_response()
             }
             Err(err) => {
                 error!("Failed to fetch balance for {chain}: {err}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": "Failed to fetch balance",
-                        "details": err.to_string()
-                    })),
-                )
-                    .into_response()
+                client_error_response("Failed to fetch balance", &err)
             }
         },
         None => {
@@ -0,0 +1,116 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use foundry_block_explorers::account::InternalTransaction;
+use mongodb::bson::{Document, doc, to_document};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, primary_name, transactions::TxQuery},
+};
+
+pub async fn get_internal_transactions(
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<TxQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Getting internal transactions for {address} on {chain}");
+    let page = params.page.unwrap_or(1);
+    let offset = params.offset.unwrap_or(10);
+
+    match state.registry.get(&chain) {
+        Some(client) => match client
+            .get_internal_transactions(address, page, offset)
+            .await
+        {
+            Ok((transactions, has_more)) => {
+                if let Some(db) = &state.mongodb {
+                    let coll_name = format!("internal_transactions_{chain}");
+                    let collection = db.database("scanza").collection::<Document>(&coll_name);
+
+                    if let Err(err) = ensure_unique_trace_index(&collection).await {
+                        error!("Failed to ensure unique index on {}: {err}", coll_name);
+                    }
+
+                    if let Err(err) =
+                        upsert_internal_transactions(&collection, transactions.clone()).await
+                    {
+                        error!("Failed to upsert transactions into {}: {err}", coll_name);
+                    }
+                }
+
+                let result = json!({
+                    "address": format!("{address:#x}"),
+                    "name": primary_name(&state, &chain, address).await,
+                    "transactions": transactions,
+                    "pagination": {
+                        "page": page,
+                        "offset": offset,
+                        "has_more": has_more,
+                        "next_page": if has_more { Some(page + 1) } else { None }
+                    }
+                });
+                (StatusCode::OK, Json(result)).into_response()
+            }
+            Err(err) => {
+                error!("Failed to fetch internal transactions: {err}");
+                client_error_response("Failed to fetch internal transactions", &err)
+            }
+        },
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// A transaction can contain several internal transactions, so they are keyed by the
+/// transaction hash together with the explorer's trace ID.
+async fn ensure_unique_trace_index<C: Sync + Send>(
+    collection: &mongodb::Collection<C>,
+) -> mongodb::error::Result<()> {
+    use mongodb::{IndexModel, options::IndexOptions};
+
+    let mut opts = IndexOptions::default();
+    opts.unique = Some(true);
+    opts.partial_filter_expression = Some(doc! {
+        "hash": { "$exists": true, "$type": "string" },
+        "traceId": { "$exists": true, "$type": "string" }
+    });
+
+    let index = IndexModel::builder()
+        .keys(doc! { "hash": 1, "traceId": 1 })
+        .options(opts)
+        .build();
+
+    collection.create_index(index).await?;
+    Ok(())
+}
+
+async fn upsert_internal_transactions(
+    collection: &mongodb::Collection<Document>,
+    txs: Vec<InternalTransaction>,
+) -> mongodb::error::Result<()> {
+    for tx in txs {
+        let doc = to_document(&tx)?;
+
+        let (Ok(hash), Ok(trace_id)) = (doc.get_str("hash"), doc.get_str("traceId")) else {
+            continue;
+        };
+
+        let filter = doc! { "hash": hash, "traceId": trace_id };
+        let update = doc! { "$setOnInsert": &doc };
+
+        collection.update_one(filter, update).upsert(true).await?;
+    }
+    Ok(())
+}
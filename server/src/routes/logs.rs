@@ -0,0 +1,126 @@
+use alloy::{
+    primitives::B256,
+    rpc::types::{Filter, Log},
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use multichain_client::{DecodedLog, EvmChainClient, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, primary_name},
+};
+
+/// Number of blocks searched, up to `to_block`, when `from_block` is not given.
+const DEFAULT_LOG_RANGE: u64 = 10_000;
+
+/// Maximum number of blocks searched by a single request.
+const MAX_LOG_RANGE: u64 = 100_000;
+
+#[derive(Debug, Deserialize)]
+pub struct LogsQuery {
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub topic0: Option<B256>,
+}
+
+/// A log with its event decoded, when the event is known.
+#[derive(Debug, Serialize)]
+pub struct DecodedLogEntry {
+    #[serde(flatten)]
+    pub log: Log,
+    pub decoded: Option<DecodedLog>,
+}
+
+pub async fn get_logs(
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<LogsQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Getting logs for {address} on {chain}");
+
+    match state.registry.get(&chain) {
+        Some(client) => {
+            let (from_block, to_block) = match block_range(client, &params).await {
+                Ok(range) => range,
+                Err(err) => {
+                    error!("Failed to fetch logs: {err}");
+                    return client_error_response("Failed to fetch logs", &err);
+                }
+            };
+            if from_block > to_block {
+                return bad_request("`from_block` must not be after `to_block`".to_string());
+            }
+            if to_block - from_block >= MAX_LOG_RANGE {
+                return bad_request(format!(
+                    "At most {MAX_LOG_RANGE} blocks can be searched at once"
+                ));
+            }
+
+            let mut filter = Filter::new().address(address);
+            if let Some(topic0) = params.topic0 {
+                filter = filter.event_signature(topic0);
+            }
+            let chain_id = client.metadata().chain_id;
+            match client.get_logs(&filter, from_block, to_block).await {
+                Ok(logs) => {
+                    let logs: Vec<_> = logs
+                        .into_iter()
+                        .map(|log| DecodedLogEntry {
+                            decoded: state.signatures.decode_log(
+                                chain_id,
+                                log.address(),
+                                log.data(),
+                            ),
+                            log,
+                        })
+                        .collect();
+
+                    let result = json!({
+                        "address": format!("{address:#x}"),
+                        "name": primary_name(&state, &chain, address).await,
+                        "from_block": from_block,
+                        "to_block": to_block,
+                        "logs": logs,
+                    });
+                    (StatusCode::OK, Json(result)).into_response()
+                }
+                Err(err) => {
+                    error!("Failed to fetch logs: {err}");
+                    client_error_response("Failed to fetch logs", &err)
+                }
+            }
+        }
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Returns the requested block range, defaulting to the last [`DEFAULT_LOG_RANGE`] blocks.
+async fn block_range(client: &EvmChainClient, params: &LogsQuery) -> Result<(u64, u64)> {
+    let to_block = match params.to_block {
+        Some(to_block) => to_block,
+        None => client.get_block_number().await?,
+    };
+    let from_block = params
+        .from_block
+        .unwrap_or_else(|| to_block.saturating_sub(DEFAULT_LOG_RANGE - 1));
+    Ok((from_block, to_block))
+}
+
+fn bad_request(error: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response()
+}
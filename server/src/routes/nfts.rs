@@ -0,0 +1,50 @@
+use alloy::primitives::Address;
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use multichain_client::NftHolding;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, primary_name},
+};
+
+#[derive(Debug, Serialize)]
+struct NftResponse {
+    address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    nfts: Vec<NftHolding>,
+}
+
+pub async fn get_nfts(
+    AddressPath { chain, address }: AddressPath,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Getting NFT holdings for {address} on {chain}");
+
+    match state.registry.get(&chain) {
+        Some(client) => {
+            let nfts = client.get_nft_holdings(address).await;
+            let name = primary_name(&state, &chain, address).await;
+            (
+                StatusCode::OK,
+                Json(NftResponse {
+                    address,
+                    name,
+                    nfts,
+                }),
+            )
+                .into_response()
+        }
+        None => {
+            warn!("Chain {chain} not found");
+            (StatusCode::NOT_FOUND, "Chain not found").into_response()
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use alloy::{primitives::B256, rpc::types::Filter};
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, logs::DecodedLogEntry},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeLogsQuery {
+    pub topic0: Option<B256>,
+}
+
+/// GET /{chain}/subscribe/heads — Upgrades to a WebSocket that pushes every new block header
+pub async fn subscribe_heads(
+    Path(chain): Path<String>,
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    info!("Subscribing to new heads on {chain}");
+
+    match state.registry.get(&chain) {
+        Some(client) => match client.subscribe_new_heads() {
+            Ok(heads) => {
+                ws.on_upgrade(move |socket| forward(socket, heads.map(|head| json!(head))))
+            }
+            Err(err) => {
+                error!("Failed to subscribe to new heads on {chain}: {err}");
+                client_error_response("Failed to subscribe to new heads", &err)
+            }
+        },
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /{chain}/subscribe/logs/{address} — Upgrades to a WebSocket that pushes every new log
+/// emitted by the address, decoded when the event is known
+pub async fn subscribe_logs(
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<SubscribeLogsQuery>,
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    info!("Subscribing to logs of {address} on {chain}");
+
+    let mut filter = Filter::new().address(address);
+    if let Some(topic0) = params.topic0 {
+        filter = filter.event_signature(topic0);
+    }
+
+    match state.registry.get(&chain) {
+        Some(client) => match client.subscribe_logs(filter) {
+            Ok(logs) => {
+                let chain_id = client.metadata().chain_id;
+                let logs = logs.map(move |log| {
+                    json!(DecodedLogEntry {
+                        decoded: state
+                            .signatures
+                            .decode_log(chain_id, log.address(), log.data()),
+                        log,
+                    })
+                });
+                ws.on_upgrade(move |socket| forward(socket, logs))
+            }
+            Err(err) => {
+                error!("Failed to subscribe to logs on {chain}: {err}");
+                client_error_response("Failed to subscribe to logs", &err)
+            }
+        },
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Sends every item of `items` to `socket` as a JSON text message, until the client
+/// disconnects.
+async fn forward(mut socket: WebSocket, items: impl Stream<Item = Value>) {
+    let mut items = std::pin::pin!(items);
+    loop {
+        tokio::select! {
+            item = items.next() => {
+                let Some(item) = item else { break };
+                if socket.send(Message::text(item.to_string())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                // messages from the client are ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("WebSocket subscriber disconnected");
+}
@@ -0,0 +1,54 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, primary_name, transactions::TxQuery},
+};
+
+pub async fn get_token_transfers(
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<TxQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Getting token transfers for {address} on {chain}");
+    let page = params.page.unwrap_or(1);
+    let offset = params.offset.unwrap_or(10);
+
+    match state.registry.get(&chain) {
+        Some(client) => match client.get_token_transfers(address, page, offset).await {
+            Ok((transfers, has_more)) => {
+                let result = json!({
+                    "address": format!("{address:#x}"),
+                    "name": primary_name(&state, &chain, address).await,
+                    "transfers": transfers,
+                    "pagination": {
+                        "page": page,
+                        "offset": offset,
+                        "has_more": has_more,
+                        "next_page": if has_more { Some(page + 1) } else { None }
+                    }
+                });
+                (StatusCode::OK, Json(result)).into_response()
+            }
+            Err(err) => {
+                error!("Failed to fetch token transfers: {err}");
+                client_error_response("Failed to fetch token transfers", &err)
+            }
+        },
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
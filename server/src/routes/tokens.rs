@@ -1,35 +1,46 @@
-use alloy::primitives::Address;
+use alloy::{eips::BlockId, primitives::Address};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 use multichain_client::TokenBalance;
 use serde::Serialize;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
-use crate::AppState;
+use crate::{
+    AppState,
+    routes::{AddressPath, BlockQuery, client_error_response, primary_name},
+};
 
 #[derive(Debug, Serialize)]
 struct TokenResponse {
     address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     tokens: Vec<TokenBalance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block: Option<BlockId>,
 }
 
 pub async fn get_tokens(
-    Path((chain, address)): Path<(String, Address)>,
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<BlockQuery>,
     State(state): State<AppState>,
 ) -> Response {
     info!("Getting token balances for {address} on {chain}");
+    let block = params.block.unwrap_or_default();
 
     match state.registry.get(&chain) {
         Some(client) => {
-            let balances = client.get_token_balances(address).await;
+            let balances = client.get_token_balances_at(address, block).await;
 
             let response = serde_json::json!(TokenResponse {
                 address,
+                name: primary_name(&state, &chain, address).await,
                 tokens: balances,
+                block: params.block,
             });
             (StatusCode::OK, Json(response)).into_response()
         }
@@ -39,3 +50,28 @@ pub async fn get_tokens(
         }
     }
 }
+
+/// POST /{chain}/tokens/{address} — Starts tracking a custom ERC-20 token on a chain
+///
+/// Responds with 201 when the token is added and 200 when it was already tracked.
+pub async fn add_token(
+    AddressPath { chain, address }: AddressPath,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Adding token {address} on {chain}");
+
+    match state.registry.get(&chain) {
+        Some(client) => match client.add_token_by_address(address).await {
+            Ok((token, true)) => (StatusCode::CREATED, Json(token)).into_response(),
+            Ok((token, false)) => (StatusCode::OK, Json(token)).into_response(),
+            Err(err) => {
+                error!("Failed to add token {address} on {chain}: {err}");
+                client_error_response("Failed to add token", &err)
+            }
+        },
+        None => {
+            warn!("Chain {chain} not found");
+            (StatusCode::NOT_FOUND, "Chain not found").into_response()
+        }
+    }
+}
@@ -1,26 +1,58 @@
+use std::collections::BTreeSet;
+
 use alloy::primitives::Address;
 use axum::{
     Json,
-    extract::{Path, Query, State},
+    extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 use foundry_block_explorers::account::NormalTransaction;
+use futures::{StreamExt, stream};
 use mongodb::bson::{Document, doc, to_document};
-use serde::Deserialize;
+use multichain_client::{DecodedCall, EvmChainClient, FeeAmount, SignatureDatabase};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, primary_name},
+};
 
-use crate::AppState;
+/// Number of contract ABIs fetched at once while decoding a page of transactions.
+const ABI_FETCH_CONCURRENCY: usize = 4;
 
 #[derive(Debug, Deserialize)]
 pub struct TxQuery {
-    page: Option<u64>,
-    offset: Option<u64>,
+    pub page: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// A transaction with its calldata decoded, when the selector is known, and the total fee it
+/// paid, including the L1 data fee on L2s.
+#[derive(Debug, Serialize)]
+pub struct DecodedTransaction {
+    #[serde(flatten)]
+    pub tx: NormalTransaction,
+    pub decoded: Option<DecodedCall>,
+    pub fee: Option<FeeAmount>,
+}
+
+impl DecodedTransaction {
+    pub fn new(
+        chain_id: u64,
+        tx: NormalTransaction,
+        fee: Option<FeeAmount>,
+        signatures: &SignatureDatabase,
+    ) -> Self {
+        let decoded = signatures.decode(chain_id, tx.to, &tx.input);
+        Self { tx, decoded, fee }
+    }
 }
 
 pub async fn get_transactions(
-    Path((chain, address)): Path<(String, Address)>,
+    AddressPath { chain, address }: AddressPath,
     Query(params): Query<TxQuery>,
     State(state): State<AppState>,
 ) -> Response {
@@ -31,22 +63,27 @@ pub async fn get_transactions(
     match state.registry.get(&chain) {
         Some(client) => match client.get_transactions(address, page, offset).await {
             Ok((transactions, has_more)) => {
-                let db = state.mongodb;
-                let coll_name = format!("transactions_{chain}");
-                let collection = db.database("scanza").collection::<Document>(&coll_name);
+                if let Some(db) = &state.mongodb {
+                    let coll_name = format!("transactions_{chain}");
+                    let collection = db.database("scanza").collection::<Document>(&coll_name);
 
-                if let Err(err) = ensure_unique_hash_index(&collection).await {
-                    error!("Failed to ensure unique index on {}: {err}", coll_name);
-                }
+                    if let Err(err) = ensure_unique_hash_index(&collection).await {
+                        error!("Failed to ensure unique index on {}: {err}", coll_name);
+                    }
 
-                if let Err(err) =
-                    upsert_transactions_by_hash(&collection, transactions.clone()).await
-                {
-                    error!("Failed to upsert transactions into {}: {err}", coll_name);
+                    if let Err(err) =
+                        upsert_transactions_by_hash(&collection, transactions.clone()).await
+                    {
+                        error!("Failed to upsert transactions into {}: {err}", coll_name);
+                    }
                 }
 
+                let transactions =
+                    decode_transactions(client, &state.signatures, transactions).await;
+
                 let result = json!({
                     "address": format!("{address:#x}"),
+                    "name": primary_name(&state, &chain, address).await,
                     "transactions": transactions,
                     "pagination": {
                         "page": page,
@@ -58,15 +95,8 @@ pub async fn get_transactions(
                 (StatusCode::OK, Json(result)).into_response()
             }
             Err(err) => {
-                error!("Failed to fetch token transfers: {err}");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({
-                        "error": "Failed to fetch token transfers",
-                        "details": err.to_string()
-                    })),
-                )
-                    .into_response()
+                error!("Failed to fetch transactions: {err}");
+                client_error_response("Failed to fetch transactions", &err)
             }
         },
         None => {
@@ -80,6 +110,41 @@ pub async fn get_transactions(
     }
 }
 
+/// Decodes the calldata of `transactions`, first fetching the verified ABI of every called
+/// contract whose selectors are unknown and whose ABI is not in `signatures` yet, and adds the
+/// fee each one paid.
+async fn decode_transactions(
+    client: &EvmChainClient,
+    signatures: &SignatureDatabase,
+    transactions: Vec<NormalTransaction>,
+) -> Vec<DecodedTransaction> {
+    let chain_id = client.metadata().chain_id;
+    let unknown: BTreeSet<Address> = transactions
+        .iter()
+        .filter(|tx| tx.input.len() >= 4 && signatures.decode(chain_id, tx.to, &tx.input).is_none())
+        .filter_map(|tx| tx.to)
+        .filter(|to| !signatures.has_abi(chain_id, *to))
+        .collect();
+
+    let abis = stream::iter(unknown)
+        .map(|address| async move { (address, client.get_contract_abi(address).await) })
+        .buffer_unordered(ABI_FETCH_CONCURRENCY)
+        .collect::<Vec<_>>();
+    let (abis, fees) = tokio::join!(abis, client.get_transaction_costs(&transactions));
+    for (address, abi) in abis {
+        match abi {
+            Ok(abi) => signatures.add_abi(chain_id, address, &abi),
+            Err(err) => debug!("No ABI for {address}: {err}"),
+        }
+    }
+
+    transactions
+        .into_iter()
+        .zip(fees)
+        .map(|(tx, fee)| DecodedTransaction::new(chain_id, tx, fee, signatures))
+        .collect()
+}
+
 async fn ensure_unique_hash_index<C: Sync + Send>(
     collection: &mongodb::Collection<C>,
 ) -> mongodb::error::Result<()> {
@@ -0,0 +1,59 @@
+use alloy::primitives::B256;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    routes::{client_error_response, logs::DecodedLogEntry},
+};
+
+/// GET /{chain}/tx/{hash} — Returns a transaction with its receipt, fees and decoded logs
+pub async fn get_transaction(
+    Path((chain, hash)): Path<(String, B256)>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("Getting transaction {hash} on {chain}");
+
+    match state.registry.get(&chain) {
+        Some(client) => match client.get_transaction_details(hash).await {
+            Ok(mut details) => {
+                let chain_id = client.metadata().chain_id;
+                let decoded = state
+                    .signatures
+                    .decode(chain_id, details.to, &details.input);
+                let logs: Vec<_> = std::mem::take(&mut details.logs)
+                    .into_iter()
+                    .map(|log| DecodedLogEntry {
+                        decoded: state
+                            .signatures
+                            .decode_log(chain_id, log.address(), log.data()),
+                        log,
+                    })
+                    .collect();
+
+                let mut result = json!(details);
+                result["decoded"] = json!(decoded);
+                result["logs"] = json!(logs);
+                (StatusCode::OK, Json(result)).into_response()
+            }
+            Err(err) => {
+                error!("Failed to fetch transaction {hash} on {chain}: {err}");
+                client_error_response("Failed to fetch transaction", &err)
+            }
+        },
+        None => {
+            warn!("Chain not found: {chain}");
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Chain not found"})),
+            )
+                .into_response()
+        }
+    }
+}
@@ -1,52 +1,116 @@
-use alloy::primitives::{Address, U256};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use multichain_client::TokenBalance;
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use multichain_client::{AddressKind, Erc1155Holding, TokenBalance};
+use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
-use crate::AppState;
+use crate::{
+    AppState,
+    routes::{AddressPath, client_error_response, deserialize_block, primary_name},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct WalletQuery {
+    #[serde(default, deserialize_with = "deserialize_block")]
+    block: Option<BlockId>,
+    /// RFC 3339 timestamp; the summary is read at the last block mined at or before it
+    at: Option<DateTime<Utc>>,
+}
 
 #[derive(Debug, Serialize)]
 struct WalletSummary {
     pub address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// What kind of account the address is at the requested block; omitted if it could not be
+    /// detected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<AddressKind>,
     pub native_balance: U256,
     pub tokens: Vec<TokenBalance>,
+    pub erc1155: Vec<Erc1155Holding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<DateTime<Utc>>,
 }
 
 pub async fn get_wallet(
-    Path((chain, address)): Path<(String, Address)>,
+    AddressPath { chain, address }: AddressPath,
+    Query(params): Query<WalletQuery>,
     State(state): State<AppState>,
 ) -> Response {
     info!("Getting wallet summary for {address} on {chain}");
 
     match state.registry.get(&chain) {
         Some(client) => {
-            let native_balance = match client.get_native_balance(address).await {
-                Ok(balance) => balance,
-                Err(err) => {
-                    error!("Failed to get wallet summary for {address} on {chain}: {err}");
+            let pinned = match (params.block, params.at) {
+                (Some(_), Some(_)) => {
                     return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({
-                            "error": "Failed to get wallet summary",
-                            "details": err.to_string()
-                        })),
+                        StatusCode::BAD_REQUEST,
+                        Json(
+                            serde_json::json!({"error": "Only one of `block` and `at` may be set"}),
+                        ),
                     )
                         .into_response();
                 }
+                (Some(block), None) => Some(block),
+                (None, Some(at)) => {
+                    let Ok(timestamp) = u64::try_from(at.timestamp()) else {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(serde_json::json!({"error": "`at` must not be before 1970"})),
+                        )
+                            .into_response();
+                    };
+                    match client.get_block_at_timestamp(timestamp).await {
+                        Ok(number) => Some(BlockId::number(number)),
+                        Err(err) => {
+                            error!("Failed to resolve block at {at} on {chain}: {err}");
+                            return client_error_response("Failed to resolve block", &err);
+                        }
+                    }
+                }
+                (None, None) => None,
+            };
+            let block = pinned.unwrap_or_default();
+
+            let native_balance = match client.get_native_balance_at(address, block).await {
+                Ok(balance) => balance,
+                Err(err) => {
+                    error!("Failed to get wallet summary for {address} on {chain}: {err}");
+                    return client_error_response("Failed to get wallet summary", &err);
+                }
             };
 
-            let tokens = client.get_token_balances(address).await;
+            let (tokens, erc1155, name, account) = tokio::join!(
+                client.get_token_balances_at(address, block),
+                client.get_erc1155_balances_at(address, block),
+                primary_name(&state, &chain, address),
+                client.classify_address_at(address, block)
+            );
+            let account = account
+                .inspect_err(|err| warn!("Failed to classify {address} on {chain}: {err}"))
+                .ok();
 
             let response = WalletSummary {
                 address,
+                name,
+                account,
                 native_balance,
                 tokens,
+                erc1155,
+                block: pinned,
+                at: params.at,
             };
 
             (StatusCode::OK, Json(response)).into_response()
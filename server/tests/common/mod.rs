@@ -0,0 +1,54 @@
+//! Helpers shared by the API tests.
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy::primitives::address;
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use mock_chain::{Fixture, MockChain};
+use mongodb::Client as MongoClient;
+use multichain_client::{EvmClientRegistry, SignatureDatabase};
+use serde_json::Value;
+use server::{AppState, init::init_router};
+use tower::ServiceExt;
+
+/// Starts a mock chain and returns it along with a router serving it as chain `dev`.
+pub async fn serve_mock() -> (MockChain, Router) {
+    serve_mock_with_mongodb(None).await
+}
+
+/// Like [`serve_mock`], storing fetched transactions in `mongodb` when set.
+pub async fn serve_mock_with_mongodb(mongodb: Option<MongoClient>) -> (MockChain, Router) {
+    let fixture = Fixture::from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../mock-chain/fixtures/dev.json"
+    ))
+    .unwrap();
+    let mock = MockChain::start(fixture).await.unwrap();
+    let mut metadata = mock.chain_metadata("Mock Devnet", "dev");
+    // the registry deployed by the fixture, as in `mock-chain/fixtures/chains.json`
+    metadata.ens_registry = Some(address!("0x00000000000000000000000000000000000E5E5a"));
+    let client = metadata.create_rpc_client("").unwrap();
+    let state = AppState {
+        registry: EvmClientRegistry::new(HashMap::from([("dev".to_string(), client)])),
+        mongodb,
+        signatures: Arc::new(SignatureDatabase::new()),
+    };
+
+    (mock, init_router(state).unwrap())
+}
+
+/// Sends a GET request for `uri`, returning the status and JSON body of the response.
+pub async fn get(router: &Router, uri: &str) -> (StatusCode, Value) {
+    let response = router
+        .clone()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
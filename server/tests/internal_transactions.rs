@@ -0,0 +1,61 @@
+//! Serves the internal transaction routes against a local mock chain.
+
+use axum::http::StatusCode;
+use mongodb::{
+    Client as MongoClient,
+    bson::{Document, doc},
+};
+
+use crate::common::{get, serve_mock, serve_mock_with_mongodb};
+
+mod common;
+
+const WALLET: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+#[tokio::test]
+async fn internal_transactions() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, body) = get(
+        &router,
+        &format!("/dev/wallet/{WALLET}/internal-transactions"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let transactions = body["transactions"].as_array().unwrap();
+    let trace_ids: Vec<_> = transactions
+        .iter()
+        .map(|tx| tx["traceId"].as_str().unwrap())
+        .collect();
+    assert_eq!(trace_ids, ["0_1", "0_0"]);
+    // both calls belong to the same transaction
+    assert_eq!(transactions[0]["hash"], transactions[1]["hash"]);
+    assert_eq!(body["pagination"]["has_more"], false);
+}
+
+#[tokio::test]
+#[ignore = "needs a MongoDB server at MONGODB_URI"]
+async fn refetched_internal_transactions_are_stored_once() {
+    let uri = std::env::var("MONGODB_URI").expect("MONGODB_URI is not set");
+    let mongodb = MongoClient::with_uri_str(&uri).await.unwrap();
+    let collection = mongodb
+        .database("scanza")
+        .collection::<Document>("internal_transactions_dev");
+    collection.drop().await.unwrap();
+    let (_mock, router) = serve_mock_with_mongodb(Some(mongodb)).await;
+
+    for _ in 0..2 {
+        let (status, _) = get(
+            &router,
+            &format!("/dev/wallet/{WALLET}/internal-transactions"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // one row per call, keyed by transaction hash and trace ID
+    assert_eq!(collection.count_documents(doc! {}).await.unwrap(), 2);
+    let trace_ids = collection.distinct("traceId", doc! {}).await.unwrap();
+    assert_eq!(trace_ids.len(), 2);
+    collection.drop().await.unwrap();
+}
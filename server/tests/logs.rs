@@ -0,0 +1,45 @@
+//! Serves the logs route against a local mock chain.
+
+use axum::http::StatusCode;
+
+use crate::common::{get, serve_mock};
+
+mod common;
+
+const TOKEN: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+
+#[tokio::test]
+async fn logs_in_range() {
+    let (_mock, router) = serve_mock().await;
+
+    let uri = format!("/dev/address/{TOKEN}/logs?from_block=990&to_block=995");
+    let (status, body) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["from_block"], 990);
+    assert_eq!(body["to_block"], 995);
+    let blocks: Vec<_> = body["logs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|log| log["blockNumber"].as_str().unwrap())
+        .collect();
+    assert_eq!(blocks, ["0x3de", "0x3e0", "0x3e1", "0x3e3"]);
+}
+
+#[tokio::test]
+async fn logs_with_inverted_range() {
+    let (_mock, router) = serve_mock().await;
+
+    let uri = format!("/dev/address/{TOKEN}/logs?from_block=995&to_block=990");
+    let (status, _) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn logs_with_too_wide_range() {
+    let (_mock, router) = serve_mock().await;
+
+    let uri = format!("/dev/address/{TOKEN}/logs?from_block=0&to_block=100000");
+    let (status, _) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
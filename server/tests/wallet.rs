@@ -0,0 +1,99 @@
+//! Serves the wallet routes against a local mock chain.
+
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::common::{get, serve_mock};
+
+mod common;
+
+const WALLET: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+#[tokio::test]
+async fn wallet_balance() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, body) = get(&router, &format!("/dev/wallet/{WALLET}/balance")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        json!({
+            "address": WALLET,
+            "name": "dev.eth",
+            "balance": "0x14d1120d7b160000",
+        })
+    );
+}
+
+#[tokio::test]
+async fn wallet_balance_at_block() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, body) = get(&router, &format!("/dev/wallet/{WALLET}/balance?block=999")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["balance"], "0x14d1120d7b160000");
+    assert_eq!(body["block"], "0x3e7");
+}
+
+#[tokio::test]
+async fn wallet_balance_by_name() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, body) = get(&router, "/dev/wallet/dev.eth/balance").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["address"], WALLET);
+}
+
+#[tokio::test]
+async fn wallet_balance_of_invalid_address() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, _) = get(&router, "/dev/wallet/0x1234/balance").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn wallet_balance_of_unknown_chain() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, _) = get(&router, &format!("/mainnet/wallet/{WALLET}/balance")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn wallet_summary_at_block() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, body) = get(&router, &format!("/dev/wallet/{WALLET}?block=999")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["account"], json!({ "kind": "eoa" }));
+    assert_eq!(body["block"], "0x3e7");
+}
+
+#[tokio::test]
+async fn wallet_summary_at_time() {
+    let (_mock, router) = serve_mock().await;
+
+    // after the latest block, mined at 2025-01-01T02:12:40Z
+    let (status, body) = get(
+        &router,
+        &format!("/dev/wallet/{WALLET}?at=2025-01-02T00:00:00Z"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["block"], "0x3e8");
+    assert_eq!(body["at"], "2025-01-02T00:00:00Z");
+    assert_eq!(body["native_balance"], "0x14d1120d7b160000");
+}
+
+#[tokio::test]
+async fn wallet_summary_at_block_and_time() {
+    let (_mock, router) = serve_mock().await;
+
+    let (status, _) = get(
+        &router,
+        &format!("/dev/wallet/{WALLET}?block=999&at=2025-01-02T00:00:00Z"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -21,12 +21,13 @@ export default function AddressInput({
 
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
-    if (!isAddress(input)) {
-      setError("Invalid EVM address");
+    // ENS names are resolved by the backend
+    if (!isAddress(input) && !/^[^.\s]+(\.[^.\s]+)+$/.test(input.trim())) {
+      setError("Invalid EVM address or ENS name");
       return;
     }
     setError("");
-    onSubmit(input);
+    onSubmit(input.trim());
     setInput("");
   };
 
@@ -36,7 +37,7 @@ export default function AddressInput({
         type="text"
         value={input}
         onChange={(e) => setInput(e.target.value)}
-        placeholder="Enter EVM address"
+        placeholder="Enter EVM address or ENS name"
       />
       <Button type="submit" className={buttonClassName}>
         Go
@@ -22,9 +22,8 @@ export default function TransactionRow({ tx, address }: TransactionRowProps) {
 	const gasFee = parseFloat(
 		formatUnits((BigInt(tx.gasUsed) * BigInt(tx.gasPrice)).toString(), 18),
 	);
-	const rawFunctionName = tx.functionName
-		? normalizeString(tx.functionName)
-		: "";
+	const signature = tx.decoded?.signature ?? tx.functionName;
+	const rawFunctionName = signature ? normalizeString(signature) : "";
 	const method = rawFunctionName
 		? formatFunctionName(rawFunctionName)
 		: "Transfer";
@@ -10,6 +10,7 @@ export type Token = {
 
 export type SummaryResponse = {
 	address: string;
+	name?: string;
 	native_balance: string;
 	tokens: Token[];
 };
@@ -17,6 +18,19 @@ export type SummaryResponse = {
 export type Address = string;
 export type Hash = string;
 
+export type DecodedArg = {
+	name: string;
+	type: string;
+	value: unknown;
+};
+
+export type DecodedCall = {
+	selector: string;
+	name: string;
+	signature: string;
+	args: DecodedArg[];
+};
+
 export type Transaction = {
 	hash: Hash;
 	from: Address;
@@ -28,4 +42,5 @@ export type Transaction = {
 	blockNumber: string;
 	input: string;
 	functionName: string;
+	decoded: DecodedCall | null;
 };